@@ -1,6 +1,9 @@
 use avian3d::prelude::{RayCaster, RayHits, TransformInterpolation};
-use bevy::app::{App, FixedUpdate, Plugin, PreStartup, Startup};
+use bevy::app::{
+    App, FixedPostUpdate, FixedUpdate, Plugin, PostUpdate, PreStartup, Startup, Update,
+};
 use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::camera::visibility::VisibilitySystems;
 use bevy::camera::{self, Camera, Camera3d, ClearColor, Exposure};
 use bevy::color::Color;
 use bevy::core_pipeline::tonemapping::Tonemapping;
@@ -8,6 +11,7 @@ use bevy::ecs::component::Component;
 use bevy::ecs::event::Event;
 use bevy::ecs::message::{Message, MessageReader};
 use bevy::ecs::resource::Resource;
+use bevy::ecs::schedule::IntoScheduleConfigs;
 use bevy::ecs::system::{Commands, Res, ResMut};
 use bevy::input::ButtonInput;
 use bevy::input::keyboard::KeyCode;
@@ -17,6 +21,7 @@ use bevy::math::{Dir3, Vec3};
 use bevy::pbr::{Atmosphere, AtmosphereMode, AtmosphereSettings, ScatteringMedium};
 use bevy::post_process::bloom::Bloom;
 use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+use bevy::transform::TransformSystems;
 use bevy::transform::components::Transform;
 use bevy::utils::default;
 use bevy_kira_audio::{Audio, AudioControl, AudioSource};
@@ -26,8 +31,16 @@ use kosim_utility::get_valid_extension;
 use kosim_utility::interpolated_value::InterpolatedValue;
 
 use crate::first_person_camera::{DynamicCameraMovement, camera_lean};
+use crate::mode::{CameraModeState, SetCameraMode, blend_camera_mode, switch_camera_mode};
+use crate::path::{
+    CameraPathConfig, CameraPathPlayback, CameraPathRecorder, control_camera_path,
+    play_camera_path, record_camera_path,
+};
+use crate::third_person_camera::{ThirdPersonConfig, third_person_camera_follow};
 
 pub mod first_person_camera;
+pub mod mode;
+pub mod path;
 pub mod third_person_camera;
 
 const DEFAULT_SCREENSHOT_FORMAT: &str = "png";
@@ -55,13 +68,37 @@ impl Plugin for KosimCameraPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CameraConfig::default())
             .insert_resource(ClearColor(Color::BLACK))
+            .init_resource::<ThirdPersonConfig>()
+            .init_resource::<CameraModeState>()
+            .init_resource::<CameraPathConfig>()
+            .init_resource::<CameraPathRecorder>()
+            .init_resource::<CameraPathPlayback>()
             .add_systems(PreStartup, create_camera)
             .add_systems(Startup, load_toggle_camera_soundfxs)
             .add_systems(
-                FixedUpdate,
-                (take_screenshot, play_toggle_camera_soundfx, camera_lean),
+                Update,
+                (
+                    control_camera_path,
+                    switch_camera_mode,
+                    play_toggle_camera_soundfx,
+                )
+                    .chain(),
             )
-            .add_message::<ToggleCameraEvent>();
+            .add_systems(FixedUpdate, (take_screenshot, camera_lean))
+            // After the player's look and motion have settled for the step.
+            .add_systems(FixedPostUpdate, third_person_camera_follow)
+            .add_systems(
+                PostUpdate,
+                (
+                    play_camera_path.before(TransformSystems::Propagate),
+                    (blend_camera_mode, record_camera_path)
+                        .chain()
+                        .after(TransformSystems::Propagate)
+                        .before(VisibilitySystems::UpdateFrusta),
+                ),
+            )
+            .add_message::<ToggleCameraEvent>()
+            .add_message::<SetCameraMode>();
     }
 }
 
@@ -108,77 +145,30 @@ pub fn create_camera(
         });
 }
 
+/// Written by [`mode::switch_camera_mode`] whenever the camera changes mode.
 #[derive(Message, Event, Clone)]
 pub struct ToggleCameraEvent {
-    mode: CameraMode,
+    pub mode: CameraMode,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum CameraMode {
+    #[default]
     FirstPerson,
     ThirdPerson,
     FreeCam,
 }
 
-// pub fn swap_camera_target(
-//     mut commands: Commands,
-//     mut ev_toggle_cam: MessageWriter<ToggleCameraEvent>,
-//     keys: Res<ButtonInput<KeyCode>>,
-//     key_bindings: Res<Bindings>,
-//     mut camera_query: Query<(Entity, &mut Transform, Option<&ChildOf>), With<GameCamera>>,
-//     player_query: Query<Entity, With<Player>>,
-//     free_camera_query: Query<Entity, With<FreeCamera>>,
-// ) {
-//     if !keys.just_pressed(key_bindings.action_toggle_camera_mode) {
-//         return;
-//     }
-
-//     let mut valid_queries: bool = true;
-//     if player_query.is_empty() {
-//         warn!("Player Query was empty, cannot swap camera parent target!");
-//         valid_queries = false;
-//     }
-
-//     if free_camera_query.is_empty() {
-//         warn!("Fly Camera Query was empty, cannot swap camera parent target!");
-//         valid_queries = false;
-//     }
-
-//     if camera_query.is_empty() {
-//         warn!("Camera Query was empty, cannot swap camera parent target!");
-//         valid_queries = false;
-//     }
-
-//     if !valid_queries {
-//         return;
-//     }
-
-//     // this is not safe, should handle none option
-//     // we first ensure that each of these entities has only one instance
-//     let player = player_query.iter().next().unwrap();
-//     let free_camera = free_camera_query.iter().next().unwrap();
-//     let (camera, mut camera_transform, camera_parent) = camera_query.iter_mut().next().unwrap();
-//     let camera_parent_unwrapped = camera_parent.unwrap();
-
-//     // check the camera to see what its parented to.
-//     // If its parented to the player, then we want to parent it to the fly camera.
-//     // else it is parented to the fly camera, and we want it parented to the player.
-//     if camera_parent_unwrapped.parent() == player {
-//         camera_transform.translation = Vec3::from_array([0.0, 0.0, 0.0]);
-//         commands.entity(free_camera).add_children(&[camera]);
-//         info!("Attached camera to fly_camera entity.");
-//         ev_toggle_cam.write(ToggleCameraEvent {
-//             mode: CameraMode::FreeCam,
-//         });
-//     } else {
-//         camera_transform.translation = Vec3::from_array([0.0, 1.0, 0.0]);
-//         commands.entity(player).add_children(&[camera]);
-//         info!("Attached camera to player entity.");
-//         ev_toggle_cam.write(ToggleCameraEvent {
-//             mode: CameraMode::FirstPerson,
-//         });
-//     }
-// }
+impl CameraMode {
+    /// The mode the toggle key cycles to from this one.
+    pub fn next(self) -> Self {
+        match self {
+            CameraMode::FirstPerson => CameraMode::ThirdPerson,
+            CameraMode::ThirdPerson => CameraMode::FreeCam,
+            CameraMode::FreeCam => CameraMode::FirstPerson,
+        }
+    }
+}
 
 #[derive(Resource)]
 pub struct ToggleCameraFreeModeAudioHandle(Handle<AudioSource>);
@@ -205,7 +195,7 @@ pub fn play_toggle_camera_soundfx(
 
     for _ev in _ev_footstep.read() {
         should_play = true;
-        mode = _ev.mode.clone();
+        mode = _ev.mode;
     }
 
     if !should_play {
@@ -247,9 +237,18 @@ pub fn take_screenshot(
         return;
     }
 
+    save_screenshot(
+        &mut commands,
+        &settings,
+        &format!("kosim-{}", Local::now().format("%Y-%m-%d_%H-%M-%S%.3f")),
+    );
+}
+
+/// Save the primary window to `./{name}`, in the configured screenshot format.
+pub fn save_screenshot(commands: &mut Commands, settings: &CameraConfig, name: &str) {
     let path: String = format!(
-        "./kosim-{}.{}",
-        Local::now().format("%Y-%m-%d_%H-%M-%S%.3f").to_string(),
+        "./{}.{}",
+        name,
         get_valid_extension(
             &settings.screenshot_format,
             kosim_utility::ExtensionType::Screenshot
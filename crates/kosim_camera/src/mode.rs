@@ -0,0 +1,176 @@
+//! Camera modes and the transitions between them.
+//!
+//! [`CameraModeState`] is the one place that knows which [`CameraMode`] the
+//! [`GameCamera`] is in. [`Bindings::action_toggle_camera_mode`] (F3) cycles first
+//! person → third person → free cam, and other systems can ask for a mode with
+//! [`SetCameraMode`]. [`switch_camera_mode`] carries out the change:
+//!
+//! - First and third person ride the [`CameraTarget`] (the player) as its child;
+//!   third person adds a [`ThirdPersonCamera`] to pull the camera out behind it.
+//! - Free cam detaches the camera, keeping its world pose, for the free-cam
+//!   controller to fly.
+//!
+//! Every change writes a [`ToggleCameraEvent`] and starts a short blend: for
+//! [`CAMERA_BLEND_DURATION`] the rendered pose eases from where the camera was to
+//! where the new mode puts it. Only the [`GlobalTransform`] is blended, after
+//! transform propagation, so the modes' own systems keep working on the real
+//! [`Transform`] throughout.
+//!
+//! Systems that drive the player from input run under [`player_control_active`], so
+//! input meant for the free cam never reaches the player; the free-cam controller
+//! runs under [`free_cam_active`].
+
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        hierarchy::ChildOf,
+        message::{Message, MessageReader, MessageWriter},
+        query::{With, Without},
+        resource::Resource,
+        system::{Commands, Query, Res, ResMut},
+    },
+    input::{ButtonInput, keyboard::KeyCode},
+    log::{info, warn},
+    math::{Quat, Vec3},
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
+use kosim_input::binding::Bindings;
+
+use crate::{CameraMode, GameCamera, ToggleCameraEvent, third_person_camera::ThirdPersonCamera};
+
+/// Seconds the rendered pose takes to ease into a new mode.
+pub const CAMERA_BLEND_DURATION: f32 = 0.35;
+
+/// Where the camera sits on its target in first person: at eye height.
+pub const FIRST_PERSON_OFFSET: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+
+/// The entity the camera rides in first and third person.
+#[derive(Component)]
+pub struct CameraTarget;
+
+/// Ask for the camera to switch to a mode.
+#[derive(Message, Clone)]
+pub struct SetCameraMode(pub CameraMode);
+
+/// The camera's current mode, and the blend into it.
+#[derive(Resource, Default)]
+pub struct CameraModeState {
+    pub mode: CameraMode,
+    blend: Option<CameraBlend>,
+}
+
+impl CameraModeState {
+    /// Whether the player should be driven by input.
+    pub fn player_control(&self) -> bool {
+        self.mode != CameraMode::FreeCam
+    }
+}
+
+struct CameraBlend {
+    /// World pose the camera was rendered at when the mode changed.
+    from: Transform,
+    elapsed: f32,
+}
+
+/// Run condition: player-control systems run only while the camera is on the player.
+pub fn player_control_active(state: Res<CameraModeState>) -> bool {
+    state.player_control()
+}
+
+/// Run condition: the free-cam controller runs only in free cam.
+pub fn free_cam_active(state: Res<CameraModeState>) -> bool {
+    state.mode == CameraMode::FreeCam
+}
+
+/// Change mode on the toggle key or a [`SetCameraMode`] request: re-parent the
+/// camera as the new mode needs and start blending into it.
+pub fn switch_camera_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut requests: MessageReader<SetCameraMode>,
+    mut state: ResMut<CameraModeState>,
+    mut ev_toggle_cam: MessageWriter<ToggleCameraEvent>,
+    mut commands: Commands,
+    targets: Query<Entity, With<CameraTarget>>,
+    mut camera_query: Query<(Entity, &mut Transform, &GlobalTransform), With<GameCamera>>,
+) {
+    let mut next = requests.read().last().map(|request| request.0);
+    if keys.just_pressed(bindings.action_toggle_camera_mode) {
+        next = Some(state.mode.next());
+    }
+    let Some(next) = next.filter(|&next| next != state.mode) else {
+        return;
+    };
+    let Ok((camera, mut transform, global)) = camera_query.single_mut() else {
+        return;
+    };
+    // What was on screen last frame, mid-blend or not.
+    let from = global.compute_transform();
+
+    if next == CameraMode::FreeCam {
+        // Detach and keep the exact world pose the camera had as a child.
+        *transform = from;
+        commands
+            .entity(camera)
+            .remove::<(ChildOf, ThirdPersonCamera)>();
+    } else {
+        let Ok(target) = targets.single() else {
+            warn!(
+                "No CameraTarget to attach the camera to, staying in {:?}",
+                state.mode
+            );
+            return;
+        };
+        if state.mode == CameraMode::FreeCam {
+            commands.entity(target).add_children(&[camera]);
+            transform.rotation = Quat::IDENTITY;
+        }
+        transform.translation = FIRST_PERSON_OFFSET;
+        if next == CameraMode::ThirdPerson {
+            commands.entity(camera).insert(ThirdPersonCamera::default());
+        } else {
+            commands.entity(camera).remove::<ThirdPersonCamera>();
+        }
+    }
+
+    info!("Camera mode: {:?} (F3 to cycle)", next);
+    state.mode = next;
+    state.blend = Some(CameraBlend { from, elapsed: 0.0 });
+    ev_toggle_cam.write(ToggleCameraEvent { mode: next });
+}
+
+/// Ease the rendered camera pose from where the last mode change left it to where
+/// the current mode puts it. Runs after transform propagation, and works out the
+/// mode's pose from the parent itself so an unchanged camera is not blended twice.
+pub fn blend_camera_mode(
+    mut state: ResMut<CameraModeState>,
+    mut camera_query: Query<(&Transform, &mut GlobalTransform, Option<&ChildOf>), With<GameCamera>>,
+    parents: Query<&GlobalTransform, Without<GameCamera>>,
+    time: Res<Time>,
+) {
+    let Some(blend) = state.blend.as_mut() else {
+        return;
+    };
+    let Ok((transform, mut global, parent)) = camera_query.single_mut() else {
+        return;
+    };
+    let target = match parent.and_then(|parent| parents.get(parent.parent()).ok()) {
+        Some(parent_global) => parent_global.mul_transform(*transform),
+        None => GlobalTransform::from(*transform),
+    }
+    .compute_transform();
+
+    blend.elapsed += time.delta_secs();
+    let t = (blend.elapsed / CAMERA_BLEND_DURATION).min(1.0);
+    let t = t * t * (3.0 - 2.0 * t);
+    *global = GlobalTransform::from(Transform {
+        translation: blend.from.translation.lerp(target.translation, t),
+        rotation: blend.from.rotation.slerp(target.rotation, t),
+        scale: target.scale,
+    });
+    if t >= 1.0 {
+        state.blend = None;
+    }
+}
@@ -0,0 +1,430 @@
+//! Camera paths: recorded flythroughs that play back the same way every time.
+//!
+//! [`Bindings::action_toggle_path_recording`] (F6) starts sampling the
+//! [`GameCamera`]'s world pose into a [`CameraPath`] every
+//! [`CameraPathConfig::sample_interval`], in any camera mode; pressing it again
+//! saves the path to [`CameraPathConfig::file`]. [`Bindings::action_toggle_path_playback`]
+//! (F7) loads that file and flies the camera along it in free cam, on a Catmull-Rom
+//! spline through the keyframe positions with the rotations slerped between them.
+//! The file carries the playback [`CameraPath::speed`] and [`PathEasing`] along with
+//! the keyframes, so a path can be tuned by hand and replays identically for reviews
+//! and performance captures. [`Bindings::action_capture_path`] (F8) plays it back
+//! while saving a screenshot every [`CameraPathConfig::capture_interval`] seconds of
+//! playback: capture steps the path by exactly that interval each frame instead of
+//! by frame time, so the same path always gives the same screenshots.
+//!
+//! [`CameraPath`] is plain data with [`CameraPath::load`] and [`CameraPath::sample`],
+//! so tools outside the game loop can replay the same files: the
+//! `replay_camera_path` example drives headless terrain streaming along one and
+//! logs its frame times.
+
+use bevy::{
+    ecs::{
+        message::MessageWriter,
+        query::With,
+        resource::Resource,
+        system::{Commands, Query, Res, ResMut},
+    },
+    input::{ButtonInput, keyboard::KeyCode},
+    log::{error, info, warn},
+    math::{Quat, Vec3},
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
+use chrono::Local;
+use kosim_input::binding::Bindings;
+use kosim_utility::toml_file::TomlFile;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CameraConfig, CameraMode, GameCamera,
+    mode::{CameraModeState, SetCameraMode},
+    save_screenshot,
+};
+
+/// Default file paths are saved to and loaded from, relative to the working
+/// directory (next to the screenshots).
+pub const DEFAULT_CAMERA_PATH_FILE: &str = "./kosim-camera-path.toml";
+
+/// Tuning for recording and capturing camera paths.
+#[derive(Resource, Clone, Debug)]
+pub struct CameraPathConfig {
+    pub file: String,
+    /// Seconds between recorded keyframes.
+    pub sample_interval: f32,
+    /// Seconds of playback between screenshots while capturing.
+    pub capture_interval: f32,
+}
+
+impl Default for CameraPathConfig {
+    fn default() -> Self {
+        Self {
+            file: DEFAULT_CAMERA_PATH_FILE.into(),
+            sample_interval: 0.25,
+            capture_interval: 0.5,
+        }
+    }
+}
+
+/// How playback progresses along the whole path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathEasing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    /// Eases in and out (smoothstep).
+    EaseInOut,
+}
+
+impl PathEasing {
+    /// Map progress `t` in `[0, 1]` to eased progress in `[0, 1]`.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            PathEasing::Linear => t,
+            PathEasing::EaseIn => t * t,
+            PathEasing::EaseOut => t * (2.0 - t),
+            PathEasing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// One sampled camera pose. Kept as plain arrays so the file stays readable.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraKeyframe {
+    /// Seconds since the start of the path.
+    pub time: f32,
+    pub translation: [f32; 3],
+    /// Quaternion as `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+}
+
+impl CameraKeyframe {
+    fn translation(&self) -> Vec3 {
+        Vec3::from_array(self.translation)
+    }
+
+    fn rotation(&self) -> Quat {
+        Quat::from_array(self.rotation).normalize()
+    }
+}
+
+/// A recorded camera path and how to play it back.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraPath {
+    /// Playback rate; 2 plays the path in half its recorded time.
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    #[serde(default)]
+    pub easing: PathEasing,
+    #[serde(rename = "keyframe", default)]
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self {
+            speed: default_speed(),
+            easing: PathEasing::default(),
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl CameraPath {
+    /// Recorded length in seconds, before [`Self::speed`].
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Seconds playback takes, at [`Self::speed`].
+    pub fn playback_duration(&self) -> f32 {
+        self.duration() / self.speed
+    }
+
+    /// Append a keyframe at `time`.
+    pub fn push(&mut self, time: f32, pose: &Transform) {
+        self.keyframes.push(CameraKeyframe {
+            time,
+            translation: pose.translation.to_array(),
+            rotation: pose.rotation.to_array(),
+        });
+    }
+
+    /// The pose after `elapsed` seconds of playback, with speed and easing applied.
+    pub fn sample_playback(&self, elapsed: f32) -> Transform {
+        let progress = match self.playback_duration() {
+            duration if duration > 0.0 => elapsed / duration,
+            _ => 1.0,
+        };
+        self.sample(self.easing.apply(progress) * self.duration())
+    }
+
+    /// The pose at recorded time `time`: a Catmull-Rom spline through the keyframe
+    /// positions, with rotations slerped between neighbouring keyframes.
+    pub fn sample(&self, time: f32) -> Transform {
+        let keyframes = &self.keyframes;
+        let Some(first) = keyframes.first() else {
+            return Transform::IDENTITY;
+        };
+        // The segment [i, i + 1] containing `time`.
+        let i = keyframes
+            .partition_point(|keyframe| keyframe.time <= time)
+            .saturating_sub(1)
+            .min(keyframes.len().saturating_sub(2));
+        let Some(next) = keyframes.get(i + 1) else {
+            return Transform::from_translation(first.translation())
+                .with_rotation(first.rotation());
+        };
+        let current = &keyframes[i];
+        let span = next.time - current.time;
+        let u = if span > 0.0 {
+            ((time - current.time) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        // Repeat the end points to give the first and last segments their tangents.
+        let p0 = keyframes[i.saturating_sub(1)].translation();
+        let p1 = current.translation();
+        let p2 = next.translation();
+        let p3 = keyframes.get(i + 2).unwrap_or(next).translation();
+        let translation = 0.5
+            * (2.0 * p1
+                + (p2 - p0) * u
+                + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * u * u
+                + (3.0 * p1 - p0 - 3.0 * p2 + p3) * u * u * u);
+        let rotation = current.rotation().slerp(next.rotation(), u);
+        Transform::from_translation(translation).with_rotation(rotation)
+    }
+
+}
+
+impl TomlFile for CameraPath {
+    /// Every problem that would stop this path from playing. Empty when it is usable.
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !(self.speed.is_finite() && self.speed > 0.0) {
+            problems.push(format!(
+                "`speed` is {}, expected a value greater than 0",
+                self.speed
+            ));
+        }
+        if self.keyframes.len() < 2 {
+            problems.push(format!(
+                "{} keyframes, expected at least 2",
+                self.keyframes.len()
+            ));
+        }
+        for (i, pair) in self.keyframes.windows(2).enumerate() {
+            if !(pair[1].time > pair[0].time) {
+                problems.push(format!(
+                    "keyframe {} is at {}s, not after the one before it",
+                    i + 1,
+                    pair[1].time
+                ));
+            }
+        }
+        for (i, keyframe) in self.keyframes.iter().enumerate() {
+            let finite = keyframe.time.is_finite()
+                && keyframe.translation.iter().all(|v| v.is_finite())
+                && keyframe.rotation.iter().all(|v| v.is_finite());
+            if !finite || Quat::from_array(keyframe.rotation).length_squared() == 0.0 {
+                problems.push(format!("keyframe {i} is not a valid pose"));
+            }
+        }
+        problems
+    }
+}
+
+/// The path being recorded, if any.
+#[derive(Resource, Default)]
+pub struct CameraPathRecorder {
+    pub recording: bool,
+    pub path: CameraPath,
+    elapsed: f32,
+    /// Seconds until the next keyframe is taken.
+    next_sample: f32,
+}
+
+/// The path being played back, if any.
+#[derive(Resource, Default)]
+pub struct CameraPathPlayback {
+    pub path: Option<CameraPath>,
+    pub elapsed: f32,
+    /// While capturing: the capture's file name prefix and the screenshots taken.
+    capture: Option<(String, u32)>,
+}
+
+impl CameraPathPlayback {
+    pub fn playing(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+/// Run condition: systems that drive the camera themselves (the free-cam controller)
+/// stand aside while a path plays.
+pub fn camera_path_idle(playback: Res<CameraPathPlayback>) -> bool {
+    !playback.playing()
+}
+
+/// Start and stop recording and playback on their keys. Starting playback switches
+/// the camera to free cam.
+pub fn control_camera_path(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    config: Res<CameraPathConfig>,
+    camera_mode: Res<CameraModeState>,
+    mut recorder: ResMut<CameraPathRecorder>,
+    mut playback: ResMut<CameraPathPlayback>,
+    mut ev_set_mode: MessageWriter<SetCameraMode>,
+) {
+    if keys.just_pressed(bindings.action_toggle_path_recording) {
+        if recorder.recording {
+            recorder.recording = false;
+            if recorder.path.keyframes.len() < 2 {
+                warn!("The camera path is too short to save");
+                return;
+            }
+            match recorder.path.save(&config.file) {
+                Ok(()) => info!(
+                    "Saved a {:.1}s camera path ({} keyframes) to {}",
+                    recorder.path.duration(),
+                    recorder.path.keyframes.len(),
+                    config.file
+                ),
+                Err(e) => error!("{}: {e}", config.file),
+            }
+        } else if playback.playing() {
+            warn!("Not recording a camera path while one is playing");
+        } else {
+            *recorder = CameraPathRecorder {
+                recording: true,
+                ..Default::default()
+            };
+            info!("Recording a camera path (F6 to stop)");
+        }
+    }
+
+    let play = keys.just_pressed(bindings.action_toggle_path_playback);
+    let capture = keys.just_pressed(bindings.action_capture_path);
+    if !play && !capture {
+        return;
+    }
+    if playback.playing() {
+        *playback = CameraPathPlayback::default();
+        info!("Stopped the camera path");
+        return;
+    }
+    if recorder.recording {
+        warn!("Not playing a camera path while one is recording");
+        return;
+    }
+    match CameraPath::load(&config.file) {
+        Ok(path) => {
+            info!(
+                "Playing {} ({:.1}s{})",
+                config.file,
+                path.playback_duration(),
+                if capture { ", capturing" } else { "" }
+            );
+            *playback = CameraPathPlayback {
+                path: Some(path),
+                elapsed: 0.0,
+                capture: capture.then(|| {
+                    let started = Local::now().format("%Y-%m-%d_%H-%M-%S");
+                    (format!("kosim-path-{started}"), 0)
+                }),
+            };
+            if camera_mode.mode != CameraMode::FreeCam {
+                ev_set_mode.write(SetCameraMode(CameraMode::FreeCam));
+            }
+        }
+        Err(e) => error!("{}: {e}", config.file),
+    }
+}
+
+/// Take a keyframe of the camera's rendered pose every sample interval while
+/// recording. Runs after transform propagation.
+pub fn record_camera_path(
+    mut recorder: ResMut<CameraPathRecorder>,
+    config: Res<CameraPathConfig>,
+    camera_query: Query<&GlobalTransform, With<GameCamera>>,
+    time: Res<Time>,
+) {
+    if !recorder.recording {
+        return;
+    }
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    let recorder = recorder.as_mut();
+    recorder.next_sample -= time.delta_secs();
+    if recorder.next_sample <= 0.0 {
+        recorder.next_sample += config.sample_interval;
+        recorder
+            .path
+            .push(recorder.elapsed, &camera.compute_transform());
+    }
+    recorder.elapsed += time.delta_secs();
+}
+
+/// Put the camera where the playing path is, and save screenshots along the way
+/// while capturing. Runs before transform propagation; switching the camera out of
+/// free cam stops playback.
+pub fn play_camera_path(
+    mut playback: ResMut<CameraPathPlayback>,
+    path_config: Res<CameraPathConfig>,
+    camera_config: Res<CameraConfig>,
+    camera_mode: Res<CameraModeState>,
+    mut camera_query: Query<&mut Transform, With<GameCamera>>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    if !playback.playing() {
+        return;
+    }
+    if camera_mode.mode != CameraMode::FreeCam {
+        info!("Stopped the camera path: the camera left free cam");
+        *playback = CameraPathPlayback::default();
+        return;
+    }
+    let Ok(mut transform) = camera_query.single_mut() else {
+        return;
+    };
+    let playback = playback.as_mut();
+    let Some(path) = &playback.path else {
+        return;
+    };
+    *transform = path.sample_playback(playback.elapsed);
+
+    let step = match &mut playback.capture {
+        // One screenshot per fixed step of playback, however long each frame takes,
+        // so a capture has the same frames on any machine.
+        Some((prefix, taken)) => {
+            save_screenshot(
+                &mut commands,
+                &camera_config,
+                &format!("{prefix}-{taken:04}"),
+            );
+            *taken += 1;
+            path_config.capture_interval
+        }
+        None => time.delta_secs(),
+    };
+
+    if playback.elapsed >= path.playback_duration() {
+        info!("Finished the camera path");
+        *playback = CameraPathPlayback::default();
+        return;
+    }
+    playback.elapsed += step;
+}
@@ -0,0 +1,153 @@
+//! Third-person chase camera.
+//!
+//! A [`GameCamera`] with [`ThirdPersonCamera`] orbits the entity it is parented to
+//! (the player) instead of sitting at its eyes. The look systems keep driving the
+//! camera's local rotation as in first person; [`third_person_camera_follow`] then
+//! places it [`ThirdPersonConfig::distance`] behind a pivot above the target's
+//! origin, shifted sideways by the shoulder offset. Being a child of the target, the
+//! orbit turns with it and so stays level with the planet's radial up.
+//!
+//! To keep the camera out of the terrain, a sphere is cast from the target's head
+//! out to the shoulder and then back toward the camera; the camera stops short of
+//! anything in the way, and eases back out once the way is clear.
+//!
+//! Reach (focus, carrying, voxel tools) is measured from the camera, so in third
+//! person it is extended by [`camera_reach_offset`].
+
+use avian3d::prelude::{Collider, ShapeCastConfig, SpatialQuery, SpatialQueryFilter};
+use bevy::{
+    ecs::{
+        component::Component,
+        hierarchy::ChildOf,
+        query::{With, Without},
+        resource::Resource,
+        system::{Query, Res},
+    },
+    math::{Dir3, Quat, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+use kosim_utility::exp_decay;
+
+use crate::GameCamera;
+
+/// Tuning for the third-person camera.
+#[derive(Resource, Clone, Debug)]
+pub struct ThirdPersonConfig {
+    /// Height of the orbit pivot above the target's origin (the first-person eye
+    /// height).
+    pub pivot_height: f32,
+    /// How far behind the pivot the camera sits when nothing is in the way.
+    pub distance: f32,
+    /// Sideways offset of the pivot; positive looks over the right shoulder.
+    pub shoulder_offset: f32,
+    /// Radius of the sphere cast to keep the camera out of geometry.
+    pub collision_radius: f32,
+    /// Rate at which the camera eases back out after an obstacle clears.
+    pub return_rate: f32,
+}
+
+impl Default for ThirdPersonConfig {
+    fn default() -> Self {
+        Self {
+            pivot_height: 1.0,
+            distance: 4.0,
+            shoulder_offset: 0.6,
+            collision_radius: 0.25,
+            return_rate: 6.0,
+        }
+    }
+}
+
+/// Puts the [`GameCamera`] in third person.
+#[derive(Component, Default)]
+pub struct ThirdPersonCamera {
+    /// Current distance behind the pivot, after collision.
+    pub distance: f32,
+}
+
+/// How much further the camera is than the player's eyes from anything in front of
+/// the player: in third person, the camera's distance behind its pivot. Reach
+/// checks measured from the camera add this, so the player reaches as far in third
+/// person as in first.
+pub fn camera_reach_offset(third_person: Option<&ThirdPersonCamera>) -> f32 {
+    third_person.map_or(0.0, |third_person| third_person.distance)
+}
+
+/// Furthest `origin` can move along `direction`, up to `max_distance`, before the
+/// camera's collision sphere touches something.
+fn clear_distance(
+    spatial_query: &SpatialQuery,
+    filter: &SpatialQueryFilter,
+    radius: f32,
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> f32 {
+    let Ok(dir) = Dir3::new(direction) else {
+        return max_distance;
+    };
+    spatial_query
+        .cast_shape(
+            &Collider::sphere(radius),
+            origin,
+            Quat::IDENTITY,
+            dir,
+            &ShapeCastConfig::from_max_distance(max_distance),
+            filter,
+        )
+        .map_or(max_distance, |hit| hit.distance)
+}
+
+/// Place third-person cameras behind their target, pulled in front of anything
+/// between the two.
+pub fn third_person_camera_follow(
+    mut cameras: Query<(&mut Transform, &mut ThirdPersonCamera, &ChildOf), With<GameCamera>>,
+    targets: Query<&Transform, Without<GameCamera>>,
+    spatial_query: SpatialQuery,
+    config: Res<ThirdPersonConfig>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut third_person, parent) in &mut cameras {
+        let Ok(target) = targets.get(parent.parent()) else {
+            continue;
+        };
+        let filter = SpatialQueryFilter::from_excluded_entities([parent.parent()]);
+        let head = Vec3::Y * config.pivot_height;
+        let to_world = |local: Vec3| target.rotation * local;
+
+        // Out to the shoulder, then back from it along the view.
+        let side = transform.rotation * Vec3::X * config.shoulder_offset.signum();
+        let shoulder = clear_distance(
+            &spatial_query,
+            &filter,
+            config.collision_radius,
+            target.translation + to_world(head),
+            to_world(side),
+            config.shoulder_offset.abs(),
+        );
+        let pivot = head + side * shoulder;
+        let back = transform.rotation * Vec3::Z;
+        let clear = clear_distance(
+            &spatial_query,
+            &filter,
+            config.collision_radius,
+            target.translation + to_world(pivot),
+            to_world(back),
+            config.distance,
+        );
+
+        // Snap in so geometry never shows through; ease back out.
+        third_person.distance = if clear < third_person.distance {
+            clear
+        } else {
+            exp_decay(
+                third_person.distance,
+                clear,
+                config.return_rate,
+                time.delta_secs(),
+            )
+        };
+        transform.translation = pivot + back * third_person.distance;
+    }
+}
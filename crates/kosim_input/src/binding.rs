@@ -1,4 +1,11 @@
-use bevy::{ecs::resource::Resource, input::{gamepad::GamepadButton, keyboard::KeyCode}};
+use bevy::{
+    ecs::resource::Resource,
+    input::{
+        ButtonInput,
+        gamepad::{Gamepad, GamepadButton},
+        keyboard::KeyCode,
+    },
+};
 
 pub enum Action {
     Jump,
@@ -11,6 +18,37 @@ pub struct Binding {
     pub button: GamepadButton,
 }
 
+impl Binding {
+    /// Whether the key or the (optional) gamepad button is held.
+    pub fn pressed(&self, keys: &ButtonInput<KeyCode>, gamepad: Option<&Gamepad>) -> bool {
+        keys.pressed(self.key) || gamepad.is_some_and(|g| g.pressed(self.button))
+    }
+
+    /// Whether the key or the (optional) gamepad button went down this frame.
+    pub fn just_pressed(&self, keys: &ButtonInput<KeyCode>, gamepad: Option<&Gamepad>) -> bool {
+        keys.just_pressed(self.key) || gamepad.is_some_and(|g| g.just_pressed(self.button))
+    }
+
+    /// Short label for on-screen prompts: the gamepad button when one is in use,
+    /// else the key.
+    pub fn label(&self, gamepad: bool) -> String {
+        if gamepad {
+            format!("{:?}", self.button)
+        } else {
+            key_label(self.key)
+        }
+    }
+}
+
+/// A key's name for on-screen prompts, e.g. `E` for `KeyCode::KeyE`.
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
 // Key configuration
 #[derive(Resource)]
 pub struct Bindings {
@@ -20,14 +58,34 @@ pub struct Bindings {
     pub move_right: KeyCode,
     pub move_ascend: KeyCode,
     pub move_descend: KeyCode,
+    pub eva_roll_left: KeyCode,
+    pub eva_roll_right: KeyCode,
     pub action_sprint: Binding,
+    pub action_jump: Binding,
     pub action_interact: Binding,
+    pub action_throw: Binding,
+    pub action_rotate_carried: KeyCode,
+    pub action_drop_item: Binding,
+    pub action_dig: Binding,
+    pub action_build: Binding,
+    pub action_toggle_brush_shape: KeyCode,
+    /// Selects the hotbar slots in order.
+    pub hotbar_slots: [KeyCode; 9],
     pub action_toggle_crouched: Binding,
     pub action_enable_freelook: Binding,
     pub action_screenshot: Binding,
     pub action_toggle_cursor_focus: KeyCode,
     pub action_toggle_camera_mode: KeyCode,
+    /// Switches the free cam between planet-relative and world axes.
+    pub action_toggle_free_cam_planet_relative: KeyCode,
+    /// Leaves free cam with the player moved to the camera.
+    pub action_free_cam_teleport: KeyCode,
+    pub action_toggle_path_recording: KeyCode,
+    pub action_toggle_path_playback: KeyCode,
+    /// Plays the camera path back while saving screenshots along it.
+    pub action_capture_path: KeyCode,
     pub action_toggle_wireframe: KeyCode,
+    pub action_toggle_world_inspector: KeyCode,
     pub action_close_application: KeyCode,
 }
 
@@ -40,10 +98,16 @@ impl Default for Bindings {
             move_right: KeyCode::KeyD,
             move_ascend: KeyCode::Space,
             move_descend: KeyCode::ShiftLeft,
+            eva_roll_left: KeyCode::KeyQ,
+            eva_roll_right: KeyCode::KeyR,
             action_sprint: Binding {
                 key: KeyCode::ShiftLeft,
                 button: GamepadButton::LeftTrigger,
             },
+            action_jump: Binding {
+                key: KeyCode::Space,
+                button: GamepadButton::South,
+            },
             action_toggle_crouched: Binding {
                 key: KeyCode::ControlLeft,
                 button: GamepadButton::LeftThumb,
@@ -53,12 +117,47 @@ impl Default for Bindings {
                 key: KeyCode::KeyE,
                 button: GamepadButton::East,
             },
+            action_throw: Binding {
+                key: KeyCode::KeyF,
+                button: GamepadButton::RightTrigger,
+            },
+            action_rotate_carried: KeyCode::KeyT,
+            action_drop_item: Binding {
+                key: KeyCode::KeyG,
+                button: GamepadButton::DPadDown,
+            },
+            action_dig: Binding {
+                key: KeyCode::KeyV,
+                button: GamepadButton::RightTrigger2,
+            },
+            action_build: Binding {
+                key: KeyCode::KeyB,
+                button: GamepadButton::LeftTrigger2,
+            },
+            action_toggle_brush_shape: KeyCode::KeyN,
+            hotbar_slots: [
+                KeyCode::Digit1,
+                KeyCode::Digit2,
+                KeyCode::Digit3,
+                KeyCode::Digit4,
+                KeyCode::Digit5,
+                KeyCode::Digit6,
+                KeyCode::Digit7,
+                KeyCode::Digit8,
+                KeyCode::Digit9,
+            ],
             action_screenshot: Binding {
                 key: KeyCode::Equal,
                 button: GamepadButton::Start,
             },
             action_toggle_camera_mode: KeyCode::F3,
+            action_toggle_free_cam_planet_relative: KeyCode::KeyP,
+            action_free_cam_teleport: KeyCode::KeyY,
+            action_toggle_path_recording: KeyCode::F6,
+            action_toggle_path_playback: KeyCode::F7,
+            action_capture_path: KeyCode::F8,
             action_toggle_wireframe: KeyCode::F4,
+            action_toggle_world_inspector: KeyCode::F5,
             action_close_application: KeyCode::Delete,
             action_enable_freelook: Binding {
                 key: KeyCode::AltLeft,
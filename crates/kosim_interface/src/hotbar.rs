@@ -0,0 +1,133 @@
+//! Hotbar HUD: the player's first inventory slots along the bottom of the screen,
+//! each showing its item's colour and count, with the selected slot outlined.
+
+use bevy::{
+    asset::AssetServer,
+    color::Color,
+    ecs::{
+        change_detection::{DetectChanges, Ref},
+        component::Component,
+        query::With,
+        system::{Commands, Query, Res},
+    },
+    text::{FontSmoothing, FontWeight, TextFont},
+    ui::{
+        AlignItems, BackgroundColor, BorderColor, JustifyContent, Node, PositionType, UiRect, Val,
+        widget::Text,
+    },
+    utils::default,
+};
+use kosim_inventory::{
+    inventory::{HOTBAR_SLOTS, Inventory},
+    item::ItemDefinitions,
+};
+use kosim_player::Player;
+
+use crate::{BORDER_COLOR, DEFAULT_FONT_PATH, GOLD_TEXT_COLOR, HUD_BACKGROUND_COLOR};
+
+const SLOT_SIZE: f32 = 44.0;
+const SWATCH_SIZE: f32 = 24.0;
+
+/// A hotbar slot's frame, by slot index.
+#[derive(Component)]
+pub struct HudHotbarSlot(pub usize);
+
+#[derive(Component)]
+pub struct HudHotbarSwatch(pub usize);
+
+#[derive(Component)]
+pub struct HudHotbarCount(pub usize);
+
+pub fn create_hotbar_hud(mut cmd: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(DEFAULT_FONT_PATH);
+
+    cmd.spawn(Node {
+        position_type: PositionType::Absolute,
+        bottom: Val::Px(16.0),
+        width: Val::Percent(100.0),
+        justify_content: JustifyContent::Center,
+        column_gap: Val::Px(4.0),
+        ..default()
+    })
+    .with_children(|parent| {
+        for slot in 0..HOTBAR_SLOTS {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(SLOT_SIZE),
+                        height: Val::Px(SLOT_SIZE),
+                        border: UiRect::all(Val::Px(2.0)),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BackgroundColor(HUD_BACKGROUND_COLOR),
+                    BorderColor::all(BORDER_COLOR),
+                    HudHotbarSlot(slot),
+                ))
+                .with_children(|slot_node| {
+                    slot_node.spawn((
+                        Node {
+                            width: Val::Px(SWATCH_SIZE),
+                            height: Val::Px(SWATCH_SIZE),
+                            ..default()
+                        },
+                        BackgroundColor(Color::NONE),
+                        HudHotbarSwatch(slot),
+                    ));
+                    slot_node.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            right: Val::Px(2.0),
+                            bottom: Val::Px(0.0),
+                            ..default()
+                        },
+                        Text::new(""),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 14.0,
+                            font_smoothing: FontSmoothing::AntiAliased,
+                            weight: FontWeight::BOLD,
+                            ..default()
+                        },
+                        HudHotbarCount(slot),
+                    ));
+                });
+        }
+    });
+}
+
+pub fn update_hotbar(
+    inventories: Query<Ref<Inventory>, With<Player>>,
+    definitions: Res<ItemDefinitions>,
+    mut slots: Query<(&HudHotbarSlot, &mut BorderColor)>,
+    mut swatches: Query<(&HudHotbarSwatch, &mut BackgroundColor)>,
+    mut counts: Query<(&HudHotbarCount, &mut Text)>,
+) {
+    let Ok(inventory) = inventories.single() else {
+        return;
+    };
+    if !inventory.is_changed() && !definitions.is_changed() {
+        return;
+    }
+    let stack = |slot: usize| inventory.slots.get(slot).and_then(Option::as_ref);
+
+    for (slot, mut border) in &mut slots {
+        *border = BorderColor::all(if slot.0 == inventory.selected {
+            GOLD_TEXT_COLOR
+        } else {
+            BORDER_COLOR
+        });
+    }
+    for (slot, mut background) in &mut swatches {
+        background.0 = stack(slot.0)
+            .and_then(|stack| definitions.get(&stack.item))
+            .map_or(Color::NONE, |definition| definition.color());
+    }
+    for (slot, mut text) in &mut counts {
+        text.0 = match stack(slot.0) {
+            Some(stack) if stack.count > 1 => stack.count.to_string(),
+            _ => String::new(),
+        };
+    }
+}
@@ -7,10 +7,11 @@ use bevy::{
         bundle::Bundle,
         component::Component,
         entity::Entity,
-        query::{QuerySingleError, With},
+        query::{Or, QuerySingleError, With, Without},
         system::{Commands, Query, Res},
     },
     image::Image,
+    input::gamepad::Gamepad,
     log::info,
     text::{Font, FontFeatures, FontSmoothing, FontWeight, TextColor, TextFont},
     ui::{
@@ -20,7 +21,15 @@ use bevy::{
     },
     utils::default,
 };
-use kosim_player::focus::{FocusTarget, ObjectInformationComponent};
+use kosim_input::binding::Bindings;
+use kosim_player::{
+    Player,
+    focus::{FocusTarget, ObjectInformationComponent},
+    interact::{InteractProgress, Interactable},
+};
+
+pub mod hotbar;
+pub mod vitals;
 
 pub const DEFAULT_FONT_PATH: &str = "fonts/AshlanderPixel_fixed.ttf";
 pub const DEFAULT_DEBUG_FONT_PATH: &str = "fonts/mononoki-Bold.ttf";
@@ -42,8 +51,25 @@ pub struct KosimInterfacePlugin;
 
 impl Plugin for KosimInterfacePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, create_sample_hud)
-            .add_systems(Update, update_focus_target_hud);
+        app.add_systems(
+            Startup,
+            (
+                create_sample_hud,
+                vitals::create_vitals_hud,
+                hotbar::create_hotbar_hud,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                update_focus_target_hud,
+                update_interact_prompt,
+                vitals::update_health_bar,
+                vitals::update_fuel_bar,
+                vitals::update_death_overlay,
+                hotbar::update_hotbar,
+            ),
+        );
     }
 }
 
@@ -86,8 +112,20 @@ pub struct HudObjectInfoRootNode;
 #[derive(Component)]
 pub struct HudObjectInfoTextNode;
 
+#[derive(Component)]
+pub struct HudInteractPromptNode;
+
+#[derive(Component)]
+pub struct HudInteractProgressBar;
+
+#[derive(Component)]
+pub struct HudInteractProgressFill;
+
 pub fn update_focus_target_hud(
-    focus_target: Query<(Entity, &FocusTarget, &ObjectInformationComponent)>,
+    focus_target: Query<
+        (Entity, &FocusTarget, Option<&ObjectInformationComponent>),
+        Or<(With<ObjectInformationComponent>, With<Interactable>)>,
+    >,
     mut root_node: Query<(&mut Node, &mut Visibility), With<HudObjectInfoRootNode>>,
     mut text_node: Query<&mut Text, With<HudObjectInfoTextNode>>,
 ) {
@@ -138,7 +176,56 @@ pub fn update_focus_target_hud(
         }
     };
 
-    text.0 = object_info.name.clone().into();
+    text.0 = object_info
+        .map(|info| info.name.clone())
+        .unwrap_or_default();
+}
+
+/// Show the focused interactable's verb with the interact key from the active
+/// bindings, and fill the progress bar while a hold-to-interact is held.
+pub fn update_interact_prompt(
+    focus_target: Query<&Interactable, With<FocusTarget>>,
+    player: Query<&InteractProgress, With<Player>>,
+    bindings: Res<Bindings>,
+    gamepads: Query<(), With<Gamepad>>,
+    mut prompt: Query<(&mut Text, &mut Visibility), With<HudInteractPromptNode>>,
+    mut bar: Query<
+        &mut Visibility,
+        (With<HudInteractProgressBar>, Without<HudInteractPromptNode>),
+    >,
+    mut fill: Query<&mut Node, With<HudInteractProgressFill>>,
+) {
+    let interactable = focus_target.iter().find(|i| i.enabled);
+    let hold = interactable.is_some_and(|i| i.hold_time > 0.0);
+    for (mut text, mut visibility) in &mut prompt {
+        match interactable {
+            Some(interactable) => {
+                let key = bindings.action_interact.label(!gamepads.is_empty());
+                let verb = interactable.verb.label();
+                text.0 = if hold {
+                    format!("Hold {key}: {verb}")
+                } else {
+                    format!("{key}: {verb}")
+                };
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    for mut visibility in &mut bar {
+        *visibility = if hold {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    let fraction = match (interactable, player.single()) {
+        (Some(interactable), Ok(progress)) => progress.fraction(interactable),
+        _ => 0.0,
+    };
+    for mut node in &mut fill {
+        node.width = Val::Percent(fraction * 100.0);
+    }
 }
 
 pub fn create_sample_hud(mut cmd: Commands, asset_server: Res<AssetServer>) {
@@ -220,9 +307,34 @@ pub fn create_sample_hud(mut cmd: Commands, asset_server: Res<AssetServer>) {
                             font_features: FontFeatures::default(),
                         },
                         TextColor(GOLD_TEXT_COLOR),
-                        Visibility::Inherited,
+                        HudInteractPromptNode,
+                        Visibility::Hidden,
                     ));
 
+                    // -- HOLD PROGRESS SEGMENT --
+                    parent
+                        .spawn((
+                            Node {
+                                width: Val::Px(96.0),
+                                height: Val::Px(4.0),
+                                ..default()
+                            },
+                            BackgroundColor(HUD_BACKGROUND_COLOR),
+                            HudInteractProgressBar,
+                            Visibility::Hidden,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Node {
+                                    width: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                BackgroundColor(GOLD_TEXT_COLOR),
+                                HudInteractProgressFill,
+                            ));
+                        });
+
                     // -- TARGET NAME SEGMENT --
                     parent.spawn((
                         Text::new("NO TARGET".to_string()),
@@ -0,0 +1,212 @@
+//! Player vitals HUD: a health bar in the bottom-left corner and a death overlay
+//! with the respawn countdown. Driven by the player's `HealthChanged`, `Died` and
+//! `Respawned` messages. A jetpack fuel bar sits above the health bar while the
+//! tank is not full.
+
+use bevy::{
+    asset::AssetServer,
+    camera::visibility::Visibility,
+    color::Color,
+    ecs::{
+        component::Component,
+        message::MessageReader,
+        query::With,
+        system::{Commands, Query, Res},
+    },
+    text::{FontSmoothing, FontWeight, TextColor, TextFont},
+    ui::{
+        AlignItems, BackgroundColor, BorderColor, FlexDirection, JustifyContent, Node,
+        PositionType, UiRect, Val, widget::Text,
+    },
+    utils::default,
+};
+use kosim_player::{
+    Player,
+    health::{Dead, Died, HealthChanged, Respawned},
+    jetpack::Jetpack,
+};
+
+use crate::{BORDER_COLOR, DEFAULT_FONT_PATH, HUD_BACKGROUND_COLOR, RED_TEXT_COLOR};
+
+const HEALTH_BAR_WIDTH: f32 = 220.0;
+const HEALTH_BAR_HEIGHT: f32 = 14.0;
+const HEALTH_FILL_COLOR: Color = Color::srgb(0.8, 0.15, 0.15);
+const FUEL_BAR_HEIGHT: f32 = 8.0;
+const FUEL_FILL_COLOR: Color = Color::srgb(0.95, 0.6, 0.1);
+
+#[derive(Component)]
+pub struct HudHealthFill;
+
+#[derive(Component)]
+pub struct HudFuelBar;
+
+#[derive(Component)]
+pub struct HudFuelFill;
+
+#[derive(Component)]
+pub struct HudDeathOverlay;
+
+#[derive(Component)]
+pub struct HudDeathText;
+
+pub fn create_vitals_hud(mut cmd: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(DEFAULT_FONT_PATH);
+
+    // Health bar
+    cmd.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            bottom: Val::Px(16.0),
+            width: Val::Px(HEALTH_BAR_WIDTH),
+            height: Val::Px(HEALTH_BAR_HEIGHT),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND_COLOR),
+        BorderColor::all(BORDER_COLOR),
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(HEALTH_FILL_COLOR),
+            HudHealthFill,
+        ));
+    });
+
+    // Jetpack fuel bar
+    cmd.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            bottom: Val::Px(16.0 + HEALTH_BAR_HEIGHT + 8.0),
+            width: Val::Px(HEALTH_BAR_WIDTH),
+            height: Val::Px(FUEL_BAR_HEIGHT),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(HUD_BACKGROUND_COLOR),
+        BorderColor::all(BORDER_COLOR),
+        HudFuelBar,
+        Visibility::Hidden,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(FUEL_FILL_COLOR),
+            HudFuelFill,
+        ));
+    });
+
+    // Death overlay
+    cmd.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.3, 0.0, 0.0, 0.35)),
+        HudDeathOverlay,
+        Visibility::Hidden,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font,
+                font_size: 32.0,
+                font_smoothing: FontSmoothing::AntiAliased,
+                weight: FontWeight::BOLD,
+                ..default()
+            },
+            TextColor(RED_TEXT_COLOR),
+            HudDeathText,
+        ));
+    });
+}
+
+pub fn update_health_bar(
+    mut ev_changed: MessageReader<HealthChanged>,
+    players: Query<(), With<Player>>,
+    mut fill: Query<&mut Node, With<HudHealthFill>>,
+) {
+    let Some(changed) = ev_changed
+        .read()
+        .filter(|c| players.contains(c.entity))
+        .last()
+    else {
+        return;
+    };
+    let fraction = if changed.max > 0.0 {
+        (changed.current / changed.max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    for mut node in &mut fill {
+        node.width = Val::Percent(fraction * 100.0);
+    }
+}
+
+pub fn update_fuel_bar(
+    jetpacks: Query<&Jetpack, With<Player>>,
+    mut bar: Query<&mut Visibility, With<HudFuelBar>>,
+    mut fill: Query<&mut Node, With<HudFuelFill>>,
+) {
+    let Ok(jetpack) = jetpacks.single() else {
+        return;
+    };
+    let fraction = if jetpack.max_fuel > 0.0 {
+        (jetpack.fuel / jetpack.max_fuel).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    for mut visibility in &mut bar {
+        *visibility = if fraction < 1.0 {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for mut node in &mut fill {
+        node.width = Val::Percent(fraction * 100.0);
+    }
+}
+
+pub fn update_death_overlay(
+    mut ev_died: MessageReader<Died>,
+    mut ev_respawned: MessageReader<Respawned>,
+    dead_players: Query<&Dead, With<Player>>,
+    mut overlay: Query<&mut Visibility, With<HudDeathOverlay>>,
+    mut text: Query<&mut Text, With<HudDeathText>>,
+) {
+    if ev_died.read().count() > 0 {
+        for mut visibility in &mut overlay {
+            *visibility = Visibility::Visible;
+        }
+    }
+    if ev_respawned.read().count() > 0 {
+        for mut visibility in &mut overlay {
+            *visibility = Visibility::Hidden;
+        }
+    }
+    if let Ok(dead) = dead_players.single()
+        && let Ok(mut text) = text.single_mut()
+    {
+        text.0 = format!(
+            "You died\nRespawning in {:.0}",
+            dead.respawn_timer.max(0.0).ceil()
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! Slot-based inventories.
+//!
+//! An [`Inventory`] is a fixed row of slots, each empty or holding one
+//! [`ItemStack`] of up to the item's `max_stack`. Adding items tops up matching
+//! stacks first and then fills empty slots in order. The first
+//! [`HOTBAR_SLOTS`] slots are the hotbar, one of which is selected.
+//!
+//! Inventories serialize with serde (items by id), so a save can store them as is;
+//! as a [`TomlFile`] they also read and write TOML directly, and
+//! [`Inventory::sanitize`] reconciles a loaded inventory with the current item
+//! definitions. The player's inventory is saved to [`PLAYER_INVENTORY_FILE`] when
+//! the app exits and loaded back, sanitized, when the player spawns.
+
+use bevy::{
+    app::AppExit,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageReader,
+        query::{With, Without},
+        system::{Commands, Query, Res},
+    },
+    input::{ButtonInput, keyboard::KeyCode},
+    log::{error, info, warn},
+};
+use kosim_input::binding::Bindings;
+use kosim_player::Player;
+use kosim_utility::toml_file::{TomlFile, TomlFileError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    item::{ItemDefinitions, ItemId},
+    tool::VoxelTool,
+};
+
+/// Slots in the player's inventory.
+pub const PLAYER_INVENTORY_SLOTS: usize = 27;
+/// The leading slots shown on the hotbar.
+pub const HOTBAR_SLOTS: usize = 9;
+/// The most slots a loaded inventory may have. A save claiming more is corrupt,
+/// and trusting it would allocate the slots before anything else is checked.
+pub const MAX_INVENTORY_SLOTS: usize = 1024;
+/// File the player's inventory is saved to on exit and loaded from at spawn,
+/// relative to the working directory.
+pub const PLAYER_INVENTORY_FILE: &str = "./kosim-inventory.toml";
+
+/// A number of one item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: ItemId, count: u32) -> Self {
+        Self { item, count }
+    }
+}
+
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "SavedInventory", into = "SavedInventory")]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+    /// The selected hotbar slot.
+    pub selected: usize,
+}
+
+/// How an [`Inventory`] is serialized: its size and only the filled slots, as
+/// formats like TOML have no way to write an empty one.
+#[derive(Serialize, Deserialize)]
+struct SavedInventory {
+    size: usize,
+    #[serde(default)]
+    selected: usize,
+    #[serde(default, rename = "slot")]
+    slots: Vec<SavedSlot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedSlot {
+    index: usize,
+    #[serde(flatten)]
+    stack: ItemStack,
+}
+
+impl From<Inventory> for SavedInventory {
+    fn from(inventory: Inventory) -> Self {
+        Self {
+            size: inventory.slots.len(),
+            selected: inventory.selected,
+            slots: inventory
+                .slots
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, stack)| {
+                    Some(SavedSlot {
+                        index,
+                        stack: stack?,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<SavedInventory> for Inventory {
+    type Error = String;
+
+    /// Slots past the saved size are left out; a size over
+    /// [`MAX_INVENTORY_SLOTS`] is rejected.
+    fn try_from(saved: SavedInventory) -> Result<Self, Self::Error> {
+        if saved.size > MAX_INVENTORY_SLOTS {
+            return Err(format!(
+                "size {} is over the limit of {MAX_INVENTORY_SLOTS} slots",
+                saved.size
+            ));
+        }
+        let mut inventory = Inventory::new(saved.size);
+        inventory.selected = saved.selected;
+        for slot in saved.slots {
+            if let Some(entry) = inventory.slots.get_mut(slot.index) {
+                *entry = Some(slot.stack);
+            }
+        }
+        Ok(inventory)
+    }
+}
+
+impl Inventory {
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+            selected: 0,
+        }
+    }
+
+    /// Add `count` of `item`, returning how many did not fit.
+    pub fn add(&mut self, item: &ItemId, count: u32, definitions: &ItemDefinitions) -> u32 {
+        let max_stack = definitions.max_stack(item);
+        let mut remaining = count;
+        for stack in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+            if &stack.item == item && stack.count < max_stack {
+                let moved = remaining.min(max_stack - stack.count);
+                stack.count += moved;
+                remaining -= moved;
+            }
+        }
+        for slot in &mut self.slots {
+            if remaining == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let moved = remaining.min(max_stack);
+                *slot = Some(ItemStack::new(item.clone(), moved));
+                remaining -= moved;
+            }
+        }
+        remaining
+    }
+
+    /// Take up to `count` items out of `slot`.
+    pub fn take(&mut self, slot: usize, count: u32) -> Option<ItemStack> {
+        let entry = self.slots.get_mut(slot)?;
+        let stack = entry.as_mut()?;
+        let taken = count.min(stack.count);
+        if taken == 0 {
+            return None;
+        }
+        stack.count -= taken;
+        let item = stack.item.clone();
+        if stack.count == 0 {
+            *entry = None;
+        }
+        Some(ItemStack::new(item, taken))
+    }
+
+    pub fn selected_stack(&self) -> Option<&ItemStack> {
+        self.slots.get(self.selected)?.as_ref()
+    }
+
+    /// How many of `item` are held across all slots.
+    pub fn count(&self, item: &ItemId) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|stack| &stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// Make a loaded inventory consistent with `definitions`: unknown items and
+    /// empty stacks are dropped, and overfull stacks are split into free slots (any
+    /// excess that does not fit is lost). Returns a description of each change.
+    pub fn sanitize(&mut self, definitions: &ItemDefinitions) -> Vec<String> {
+        let mut changes = Vec::new();
+        let mut overflow = Vec::new();
+        for slot in &mut self.slots {
+            let Some(stack) = slot.as_mut() else {
+                continue;
+            };
+            if definitions.get(&stack.item).is_none() {
+                changes.push(format!("removed unknown item `{}`", stack.item));
+                *slot = None;
+                continue;
+            }
+            if stack.count == 0 {
+                *slot = None;
+                continue;
+            }
+            let max_stack = definitions.max_stack(&stack.item);
+            if stack.count > max_stack {
+                overflow.push(ItemStack::new(stack.item.clone(), stack.count - max_stack));
+                stack.count = max_stack;
+            }
+        }
+        for stack in overflow {
+            let lost = self.add(&stack.item, stack.count, definitions);
+            if lost > 0 {
+                changes.push(format!("no room for {lost} `{}`", stack.item));
+            }
+        }
+        if self.selected >= HOTBAR_SLOTS.min(self.slots.len()) {
+            self.selected = 0;
+        }
+        changes
+    }
+}
+
+/// Nothing to validate on its own: item references are checked against the
+/// definitions by [`Inventory::sanitize`] once those have loaded.
+impl TomlFile for Inventory {}
+
+/// The saved player inventory reconciled with `definitions`, or an empty one if
+/// there is no usable save.
+fn load_player_inventory(definitions: &ItemDefinitions) -> Inventory {
+    let mut inventory = match Inventory::load(PLAYER_INVENTORY_FILE) {
+        Ok(inventory) => inventory,
+        Err(TomlFileError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Inventory::new(PLAYER_INVENTORY_SLOTS);
+        }
+        Err(e) => {
+            warn!("{PLAYER_INVENTORY_FILE}: {e}; starting with an empty inventory");
+            return Inventory::new(PLAYER_INVENTORY_SLOTS);
+        }
+    };
+    // A save from a smaller inventory gains the new slots; a bigger one keeps its own.
+    if inventory.slots.len() < PLAYER_INVENTORY_SLOTS {
+        inventory.slots.resize(PLAYER_INVENTORY_SLOTS, None);
+    }
+    for change in inventory.sanitize(definitions) {
+        warn!("{PLAYER_INVENTORY_FILE}: {change}");
+    }
+    info!("Loaded the inventory from {PLAYER_INVENTORY_FILE}");
+    inventory
+}
+
+/// Give players their saved inventory (or an empty one) and a voxel tool. Waits
+/// for the item definitions to load, so the save can be sanitized against them.
+pub fn add_player_inventory(
+    players: Query<Entity, (With<Player>, Without<Inventory>)>,
+    definitions: Res<ItemDefinitions>,
+    mut commands: Commands,
+) {
+    if definitions.items.is_empty() {
+        return;
+    }
+    for player in &players {
+        commands
+            .entity(player)
+            .insert((load_player_inventory(&definitions), VoxelTool::default()));
+    }
+}
+
+/// Save the player's inventory to [`PLAYER_INVENTORY_FILE`] when the app exits.
+pub fn save_player_inventory(
+    mut exits: MessageReader<AppExit>,
+    inventories: Query<&Inventory, With<Player>>,
+) {
+    if exits.is_empty() {
+        return;
+    }
+    exits.clear();
+    let Ok(inventory) = inventories.single() else {
+        return;
+    };
+    match inventory.save(PLAYER_INVENTORY_FILE) {
+        Ok(()) => info!("Saved the inventory to {PLAYER_INVENTORY_FILE}"),
+        Err(e) => error!("{PLAYER_INVENTORY_FILE}: {e}"),
+    }
+}
+
+/// Select a hotbar slot with its bound key.
+pub fn select_hotbar_slot(
+    mut inventories: Query<&mut Inventory, With<Player>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+) {
+    let Some(slot) = bindings
+        .hotbar_slots
+        .iter()
+        .position(|key| keys.just_pressed(*key))
+    else {
+        return;
+    };
+    for mut inventory in &mut inventories {
+        if slot < inventory.slots.len().min(HOTBAR_SLOTS) {
+            inventory.selected = slot;
+        } else {
+            warn!("Inventory: no hotbar slot {}", slot + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions() -> ItemDefinitions {
+        ItemDefinitions::from_toml(
+            r#"
+            [[item]]
+            id = "stone"
+            name = "Stone"
+            max_stack = 64
+
+            [[item]]
+            id = "lamp"
+            name = "Lamp"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_gaps_and_counts() {
+        let mut inventory = Inventory::new(6);
+        inventory.slots[1] = Some(ItemStack::new(ItemId::new("stone"), 12));
+        // Overfull, beyond the stone's `max_stack` of 64.
+        inventory.slots[4] = Some(ItemStack::new(ItemId::new("stone"), 150));
+        inventory.selected = 4;
+
+        let text = inventory.to_toml().unwrap();
+        let loaded = Inventory::from_toml(&text).unwrap();
+        assert_eq!(loaded.slots, inventory.slots);
+        assert_eq!(loaded.selected, 4);
+    }
+
+    #[test]
+    fn oversized_save_is_rejected() {
+        // The largest size TOML can hold; allocating it would abort.
+        let text = r#"
+            size = 9223372036854775807
+
+            [[slot]]
+            index = 0
+            item = "stone"
+            count = 1
+            "#;
+        let error = Inventory::from_toml(text).unwrap_err();
+        assert!(error.to_string().contains("over the limit"), "{error}");
+    }
+
+    #[test]
+    fn sanitize_splits_overfull_stacks_and_drops_unknown_items() {
+        let mut inventory = Inventory::new(4);
+        inventory.slots[0] = Some(ItemStack::new(ItemId::new("stone"), 150));
+        inventory.slots[2] = Some(ItemStack::new(ItemId::new("unobtainium"), 1));
+        inventory.slots[3] = Some(ItemStack::new(ItemId::new("lamp"), 0));
+        let mut loaded = Inventory::from_toml(&inventory.to_toml().unwrap()).unwrap();
+
+        let changes = loaded.sanitize(&definitions());
+        let stone = |count| Some(ItemStack::new(ItemId::new("stone"), count));
+        // The excess 86 tops up nothing, so it fills the free slots in order.
+        assert_eq!(loaded.slots, vec![stone(64), stone(64), stone(22), None]);
+        assert_eq!(
+            changes,
+            vec!["removed unknown item `unobtainium`".to_string()]
+        );
+    }
+
+    #[test]
+    fn sanitize_reports_what_does_not_fit() {
+        let mut inventory = Inventory::new(2);
+        inventory.slots[0] = Some(ItemStack::new(ItemId::new("stone"), 200));
+        inventory.selected = 5;
+        let mut loaded = Inventory::from_toml(&inventory.to_toml().unwrap()).unwrap();
+
+        let changes = loaded.sanitize(&definitions());
+        assert_eq!(loaded.count(&ItemId::new("stone")), 128);
+        assert_eq!(changes, vec!["no room for 72 `stone`".to_string()]);
+        assert_eq!(loaded.selected, 0);
+    }
+}
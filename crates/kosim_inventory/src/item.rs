@@ -0,0 +1,206 @@
+//! Item definitions, loaded from [`ITEM_DEFINITIONS_PATH`].
+//!
+//! Each `[[item]]` entry in the file describes one kind of item: the id that
+//! inventories and world items refer to it by, its display name, how many fit in
+//! one slot, how it looks and weighs when it is dropped into the world, and the
+//! voxel material it is dug out as and built with, if any. Like the
+//! player config, the file is hot reloaded; a file that fails to load or validate is
+//! reported and the current definitions are kept.
+
+use std::fmt;
+
+use bevy::{
+    asset::{Asset, AssetEvent, AssetLoadFailedEvent, AssetServer, Assets, Handle},
+    color::Color,
+    ecs::{
+        message::MessageReader,
+        resource::Resource,
+        system::{Commands, Res, ResMut},
+    },
+    log::{error, info},
+    reflect::TypePath,
+};
+use kosim_utility::toml_file::TomlFile;
+use kosim_world::voxel::VoxelMaterial;
+use serde::{Deserialize, Serialize};
+
+/// Asset path of the item definitions, relative to `assets/`.
+pub const ITEM_DEFINITIONS_PATH: &str = "config/items.toml";
+
+/// Refers to an [`ItemDefinition`] by its `id`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemId(pub String);
+
+impl ItemId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+}
+
+impl fmt::Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// One kind of item.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDefinition {
+    pub id: ItemId,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Most of this item a single inventory slot holds.
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
+    /// Edge length of the cube it is dropped as.
+    #[serde(default = "default_size")]
+    pub size: f32,
+    /// Mass of one dropped item.
+    #[serde(default = "default_mass")]
+    pub mass: f32,
+    /// sRGB colour of the dropped cube and its hotbar swatch.
+    #[serde(default = "default_color")]
+    pub color: [f32; 3],
+    /// Name of the voxel material this item is dug out as and builds (see
+    /// [`VoxelMaterial::name`]).
+    #[serde(default)]
+    pub voxel: Option<String>,
+}
+
+fn default_max_stack() -> u32 {
+    1
+}
+
+fn default_size() -> f32 {
+    0.3
+}
+
+fn default_mass() -> f32 {
+    1.0
+}
+
+fn default_color() -> [f32; 3] {
+    [0.8, 0.8, 0.8]
+}
+
+impl ItemDefinition {
+    pub fn color(&self) -> Color {
+        let [r, g, b] = self.color;
+        Color::srgb(r, g, b)
+    }
+
+    pub fn voxel_material(&self) -> Option<VoxelMaterial> {
+        self.voxel.as_deref().and_then(VoxelMaterial::from_name)
+    }
+}
+
+/// Every known item. Empty until [`ITEM_DEFINITIONS_PATH`] loads.
+#[derive(Resource, Asset, TypePath, Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDefinitions {
+    #[serde(rename = "item", default)]
+    pub items: Vec<ItemDefinition>,
+}
+
+impl ItemDefinitions {
+    pub fn get(&self, id: &ItemId) -> Option<&ItemDefinition> {
+        self.items.iter().find(|item| &item.id == id)
+    }
+
+    /// The item a voxel of `material` is dug out as.
+    pub fn for_voxel(&self, material: VoxelMaterial) -> Option<&ItemDefinition> {
+        self.items
+            .iter()
+            .find(|item| item.voxel_material() == Some(material))
+    }
+
+    /// How many of `id` fit in one slot; unknown items don't stack.
+    pub fn max_stack(&self, id: &ItemId) -> u32 {
+        self.get(id).map_or(1, |item| item.max_stack)
+    }
+}
+
+impl TomlFile for ItemDefinitions {
+    /// Every invalid entry, described by id. Empty when the definitions are usable.
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            if item.id.0.is_empty() {
+                problems.push(format!("item {i} has an empty `id`"));
+            }
+            if self.items[..i].iter().any(|other| other.id == item.id) {
+                problems.push(format!("`{}` is defined more than once", item.id));
+            }
+            if item.max_stack == 0 {
+                problems.push(format!("`{}`: `max_stack` must be at least 1", item.id));
+            }
+            if !(item.size.is_finite() && item.size > 0.0) {
+                problems.push(format!(
+                    "`{}`: `size` is {}, expected a value greater than 0",
+                    item.id, item.size
+                ));
+            }
+            if !(item.mass.is_finite() && item.mass > 0.0) {
+                problems.push(format!(
+                    "`{}`: `mass` is {}, expected a value greater than 0",
+                    item.id, item.mass
+                ));
+            }
+            if let Some(voxel) = &item.voxel
+                && VoxelMaterial::from_name(voxel).is_none()
+            {
+                problems.push(format!(
+                    "`{}`: `voxel` \"{voxel}\" is not a voxel material",
+                    item.id
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// Keeps the loaded definitions asset alive (and watched for changes).
+#[derive(Resource)]
+pub struct ItemDefinitionsHandle(pub Handle<ItemDefinitions>);
+
+pub fn load_item_definitions(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ItemDefinitionsHandle(
+        asset_server.load(ITEM_DEFINITIONS_PATH),
+    ));
+}
+
+/// Copy the definitions asset into the [`ItemDefinitions`] resource whenever it
+/// (re)loads.
+pub fn apply_item_definitions(
+    handle: Option<Res<ItemDefinitionsHandle>>,
+    assets: Res<Assets<ItemDefinitions>>,
+    mut asset_events: MessageReader<AssetEvent<ItemDefinitions>>,
+    mut failed_events: MessageReader<AssetLoadFailedEvent<ItemDefinitions>>,
+    mut definitions: ResMut<ItemDefinitions>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for failed in failed_events.read() {
+        if failed.id == handle.0.id() {
+            error!(
+                "{}: {}; keeping the current item definitions",
+                failed.path, failed.error
+            );
+        }
+    }
+    for event in asset_events.read() {
+        if (event.is_added(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = assets.get(&handle.0)
+        {
+            *definitions = loaded.clone();
+            info!(
+                "Loaded {} item definitions from {ITEM_DEFINITIONS_PATH}",
+                definitions.items.len()
+            );
+        }
+    }
+}
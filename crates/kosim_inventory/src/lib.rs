@@ -0,0 +1,51 @@
+//! `kosim_inventory` — items and what the player carries in their pockets.
+//!
+//! Item kinds are data: [`item::ItemDefinitions`] loads them from
+//! `config/items.toml`. The player gets a slot-based [`inventory::Inventory`] whose
+//! first slots form the hotbar; [`world_item::WorldItem`]s are stacks lying in the
+//! world as physics props, taken through the player's interaction and dropped back
+//! out from the selected hotbar slot. The [`tool::VoxelTool`] digs terrain into the
+//! inventory and builds it back out.
+
+use bevy::prelude::*;
+use kosim_camera::mode::player_control_active;
+use kosim_utility::toml_file::TomlFileLoader;
+
+pub mod inventory;
+pub mod item;
+pub mod tool;
+pub mod world_item;
+
+use item::{ItemDefinitions, apply_item_definitions, load_item_definitions};
+
+pub struct KosimInventoryPlugin;
+
+impl Plugin for KosimInventoryPlugin {
+    fn build(&self, app: &mut App) {
+        // Empty until `config/items.toml` loads; edits to it are hot reloaded.
+        app.init_resource::<ItemDefinitions>()
+            .init_resource::<tool::VoxelToolConfig>()
+            .init_asset::<ItemDefinitions>()
+            .register_asset_loader(TomlFileLoader::<ItemDefinitions>::default())
+            .add_systems(Startup, load_item_definitions)
+            .add_systems(
+                Update,
+                (
+                    apply_item_definitions,
+                    inventory::add_player_inventory,
+                    world_item::build_world_items,
+                    world_item::take_world_items,
+                    (
+                        inventory::select_hotbar_slot,
+                        world_item::drop_selected_item,
+                        // Edits apply in the same frame.
+                        tool::use_voxel_tools.before(kosim_world::edit::apply_voxel_edits),
+                    )
+                        .run_if(player_control_active),
+                )
+                    .chain(),
+            )
+            .add_systems(FixedUpdate, world_item::apply_world_item_gravity)
+            .add_systems(Last, inventory::save_player_inventory);
+    }
+}
@@ -0,0 +1,229 @@
+//! Digging and building voxels.
+//!
+//! The player's [`VoxelTool`] works on the terrain surface under the crosshair,
+//! within [`VoxelToolConfig::reach`]: digging removes the voxels inside a sphere or
+//! cube brush centred on the hit voxel, and building fills the empty voxels of the
+//! brush centred just outside the hit face. Holding either action repeats it.
+//!
+//! Dug voxels are collected as the item whose definition names their material
+//! (`voxel = "stone"`); whatever does not fit in the inventory is dropped at the
+//! dig site. Building spends the selected hotbar item, which must name a material,
+//! and never fills a voxel the player is standing in. Edits go through
+//! [`EditVoxels`], so the world swaps in the new colliders in the same frame.
+
+use std::collections::HashMap;
+
+use avian3d::prelude::{Collider, SpatialQuery, SpatialQueryFilter};
+use bevy::{
+    ecs::{
+        component::Component,
+        message::MessageWriter,
+        query::{With, Without},
+        resource::Resource,
+        system::{Commands, Query, Res},
+    },
+    input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
+    log::info,
+    math::{IVec3, Quat},
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
+use kosim_camera::{
+    GameCamera,
+    third_person_camera::{ThirdPersonCamera, camera_reach_offset},
+};
+use kosim_input::binding::Bindings;
+use kosim_player::{Player, focus::FOCUS_DISTANCE, health::Dead, layers::GameLayer};
+use kosim_world::{
+    ChunkManager, VoxelWorld,
+    edit::{EditVoxels, VoxelChange},
+    voxel::VoxelMaterial,
+};
+
+use crate::{
+    inventory::{Inventory, ItemStack},
+    item::ItemDefinitions,
+    world_item::WorldItem,
+};
+
+/// Tuning for the voxel tools.
+#[derive(Resource, Clone, Debug)]
+pub struct VoxelToolConfig {
+    /// Farthest surface the tools reach, from the camera in first person (further
+    /// by [`camera_reach_offset`] in third).
+    pub reach: f32,
+    /// Brush radius (half the edge of a cube brush), in world units. Anything under
+    /// half a voxel edits a single voxel.
+    pub brush_radius: f32,
+    /// Seconds between repeats while an action is held.
+    pub repeat_interval: f32,
+}
+
+impl Default for VoxelToolConfig {
+    fn default() -> Self {
+        Self {
+            reach: FOCUS_DISTANCE,
+            brush_radius: 0.75,
+            repeat_interval: 0.25,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BrushShape {
+    #[default]
+    Sphere,
+    Cube,
+}
+
+/// The player's digging and building tool.
+#[derive(Component, Default)]
+pub struct VoxelTool {
+    pub shape: BrushShape,
+    /// Seconds until a held action repeats.
+    pub cooldown: f32,
+}
+
+/// The voxels of a `shape` brush of `radius` voxels around `center`, nearest first.
+fn brush_voxels(center: IVec3, radius: f32, shape: BrushShape) -> Vec<IVec3> {
+    let extent = radius.floor() as i32;
+    let mut voxels = Vec::new();
+    for z in -extent..=extent {
+        for y in -extent..=extent {
+            for x in -extent..=extent {
+                let offset = IVec3::new(x, y, z);
+                if shape == BrushShape::Cube || offset.as_vec3().length() <= radius {
+                    voxels.push(offset);
+                }
+            }
+        }
+    }
+    voxels.sort_by_key(|offset| offset.length_squared());
+    voxels.into_iter().map(|offset| center + offset).collect()
+}
+
+fn material_at(world: &VoxelWorld, v: IVec3) -> Option<VoxelMaterial> {
+    world.voxel_material(v.x as i64, v.y as i64, v.z as i64)
+}
+
+/// Switch the brush shape, and dig or build with the bound actions.
+pub fn use_voxel_tools(
+    mut players: Query<(&mut Inventory, &mut VoxelTool), (With<Player>, Without<Dead>)>,
+    camera_query: Query<(&GlobalTransform, Option<&ThirdPersonCamera>), With<GameCamera>>,
+    chunks: Option<Res<ChunkManager>>,
+    definitions: Res<ItemDefinitions>,
+    config: Res<VoxelToolConfig>,
+    spatial_query: SpatialQuery,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut ev_edit: MessageWriter<EditVoxels>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let (Some(chunks), Ok((camera, third_person))) = (chunks, camera_query.single()) else {
+        return;
+    };
+    let reach = config.reach + camera_reach_offset(third_person);
+    let world = chunks.world();
+    let gamepad = gamepad_query.single().ok();
+    let dig = bindings.action_dig.pressed(&keys, gamepad);
+    let build = bindings.action_build.pressed(&keys, gamepad);
+
+    for (mut inventory, mut tool) in &mut players {
+        if keys.just_pressed(bindings.action_toggle_brush_shape) {
+            tool.shape = match tool.shape {
+                BrushShape::Sphere => BrushShape::Cube,
+                BrushShape::Cube => BrushShape::Sphere,
+            };
+            info!("Voxel tool: {:?} brush", tool.shape);
+        }
+        if !dig && !build {
+            tool.cooldown = 0.0;
+            continue;
+        }
+        tool.cooldown -= time.delta_secs();
+        if tool.cooldown > 0.0 {
+            continue;
+        }
+        tool.cooldown = config.repeat_interval;
+
+        let origin = camera.translation();
+        let Some(hit) = world.raycast(origin, camera.forward().as_vec3(), reach) else {
+            continue;
+        };
+        let radius = config.brush_radius / world.config.min_voxel_size;
+
+        let changes: Vec<VoxelChange> = if dig {
+            let mut dug: HashMap<VoxelMaterial, u32> = HashMap::new();
+            let changes: Vec<VoxelChange> = brush_voxels(hit.voxel, radius, tool.shape)
+                .into_iter()
+                .filter_map(|v| {
+                    let material = material_at(world, v)?;
+                    *dug.entry(material).or_default() += 1;
+                    Some((v, None))
+                })
+                .collect();
+            for (material, count) in dug {
+                // Materials with no item are simply lost.
+                let Some(definition) = definitions.for_voxel(material) else {
+                    continue;
+                };
+                let left = inventory.add(&definition.id, count, &definitions);
+                if left > 0 {
+                    commands.spawn((
+                        WorldItem {
+                            stack: ItemStack::new(definition.id.clone(), left),
+                        },
+                        Transform::from_translation(hit.point + hit.normal * definition.size),
+                    ));
+                }
+            }
+            changes
+        } else {
+            let Some(stack) = inventory.selected_stack() else {
+                continue;
+            };
+            let Some(material) = definitions
+                .get(&stack.item)
+                .and_then(|definition| definition.voxel_material())
+            else {
+                continue;
+            };
+            let center = hit.voxel + hit.normal.round().as_ivec3();
+            // The player must never be built into.
+            let voxel_size = world.config.min_voxel_size;
+            let voxel_shape = Collider::cuboid(voxel_size, voxel_size, voxel_size);
+            let player_filter = SpatialQueryFilter::from_mask(GameLayer::Player);
+            let overlaps_player = |v: IVec3| {
+                !spatial_query
+                    .shape_intersections(
+                        &voxel_shape,
+                        world.voxel_center(v),
+                        Quat::IDENTITY,
+                        &player_filter,
+                    )
+                    .is_empty()
+            };
+            let changes: Vec<VoxelChange> = brush_voxels(center, radius, tool.shape)
+                .into_iter()
+                .filter(|&v| material_at(world, v).is_none() && !overlaps_player(v))
+                .take(stack.count as usize)
+                .map(|v| (v, Some(material)))
+                .collect();
+            let selected = inventory.selected;
+            inventory.take(selected, changes.len() as u32);
+            changes
+        };
+
+        if changes.is_empty() {
+            continue;
+        }
+        info!(
+            "Voxel tool: {} {} voxels",
+            if dig { "dug" } else { "built" },
+            changes.len()
+        );
+        ev_edit.write(EditVoxels { changes });
+    }
+}
@@ -0,0 +1,184 @@
+//! Items lying in the world.
+//!
+//! A [`WorldItem`] is a stack of one item as a physics prop: a dynamic cube sized
+//! and coloured by its [`ItemDefinition`], pulled toward the planet like debris.
+//! Spawning one only needs the component and a `Transform`; the body, mesh and
+//! [`Interactable`] are added by [`build_world_items`] once the definitions have
+//! loaded. Taking it (the [`InteractVerb::Take`] interaction) moves as much of the
+//! stack as fits into the player's [`Inventory`]; dropping puts the selected hotbar
+//! item back into the world in front of the camera.
+
+use avian3d::prelude::{Collider, LinearVelocity, Mass, RigidBody};
+use bevy::{
+    asset::Assets,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageReader,
+        query::{With, Without},
+        system::{Commands, Query, Res, ResMut},
+    },
+    input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
+    log::info,
+    math::primitives::Cuboid,
+    mesh::{Mesh, Mesh3d},
+    pbr::{MeshMaterial3d, StandardMaterial},
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
+use kosim_camera::GameCamera;
+use kosim_input::binding::Bindings;
+use kosim_player::{
+    Player,
+    focus::ObjectInformationComponent,
+    gravity::{PlanetGravity, up_at},
+    health::Dead,
+    interact::{InteractEvent, InteractVerb, Interactable},
+};
+
+use crate::{
+    inventory::{Inventory, ItemStack},
+    item::{ItemDefinition, ItemDefinitions, ItemId},
+};
+
+/// How far in front of the camera a dropped item appears.
+const DROP_DISTANCE: f32 = 0.8;
+/// Speed a dropped item is tossed forward at, on top of the player's own.
+const DROP_SPEED: f32 = 3.0;
+
+/// A stack of items lying in the world.
+#[derive(Component)]
+pub struct WorldItem {
+    pub stack: ItemStack,
+}
+
+impl WorldItem {
+    pub fn new(item: impl Into<String>, count: u32) -> Self {
+        Self {
+            stack: ItemStack::new(ItemId::new(item), count),
+        }
+    }
+}
+
+/// The focus panel text for `count` of `definition`.
+fn item_information(definition: &ItemDefinition, count: u32) -> ObjectInformationComponent {
+    let name = if count > 1 {
+        format!("{} ×{count}", definition.name)
+    } else {
+        definition.name.clone()
+    };
+    ObjectInformationComponent {
+        name,
+        description: definition.description.clone(),
+    }
+}
+
+/// Give new world items their body, mesh and interaction once their item is known.
+pub fn build_world_items(
+    items: Query<(Entity, &WorldItem), Without<Mesh3d>>,
+    definitions: Res<ItemDefinitions>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    for (entity, item) in &items {
+        let Some(definition) = definitions.get(&item.stack.item) else {
+            continue;
+        };
+        let size = definition.size;
+        commands.entity(entity).insert((
+            RigidBody::Dynamic,
+            Collider::cuboid(size, size, size),
+            Mass(definition.mass * item.stack.count as f32),
+            Mesh3d(meshes.add(Cuboid::from_length(size))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: definition.color(),
+                ..Default::default()
+            })),
+            item_information(definition, item.stack.count),
+            Interactable::new(InteractVerb::Take),
+        ));
+    }
+}
+
+/// Pull world items toward the planet centre; physics runs without global gravity.
+pub fn apply_world_item_gravity(
+    mut items: Query<(&Transform, &mut LinearVelocity), With<WorldItem>>,
+    gravity: Res<PlanetGravity>,
+    time: Res<Time>,
+) {
+    for (transform, mut velocity) in &mut items {
+        let down = -up_at(transform.translation, gravity.center);
+        velocity.0 += down * gravity.strength * time.delta_secs();
+    }
+}
+
+/// Move taken world items into the taker's inventory. Whatever does not fit stays
+/// in the world.
+pub fn take_world_items(
+    mut ev_interact: MessageReader<InteractEvent>,
+    mut players: Query<&mut Inventory, With<Player>>,
+    mut items: Query<&mut WorldItem>,
+    definitions: Res<ItemDefinitions>,
+    mut commands: Commands,
+) {
+    for event in ev_interact.read() {
+        if event.verb != InteractVerb::Take {
+            continue;
+        }
+        let (Ok(mut inventory), Ok(mut item)) =
+            (players.get_mut(event.player), items.get_mut(event.target))
+        else {
+            continue;
+        };
+        let stack = &mut item.stack;
+        let left = inventory.add(&stack.item, stack.count, &definitions);
+        info!("Inventory: took {} {}", stack.count - left, stack.item);
+        if left == 0 {
+            commands.entity(event.target).despawn();
+            continue;
+        }
+        stack.count = left;
+        if let Some(definition) = definitions.get(&stack.item) {
+            commands.entity(event.target).insert((
+                Mass(definition.mass * left as f32),
+                item_information(definition, left),
+            ));
+        }
+    }
+}
+
+/// Drop one of the selected hotbar item, or the whole stack while sprint is held,
+/// tossed forward from the camera.
+pub fn drop_selected_item(
+    mut players: Query<(&mut Inventory, &LinearVelocity), (With<Player>, Without<Dead>)>,
+    camera_query: Query<&GlobalTransform, With<GameCamera>>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut commands: Commands,
+) {
+    let gamepad = gamepad_query.single().ok();
+    if !bindings.action_drop_item.just_pressed(&keys, gamepad) {
+        return;
+    }
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    let whole_stack = bindings.action_sprint.pressed(&keys, gamepad);
+    for (mut inventory, player_velocity) in &mut players {
+        let selected = inventory.selected;
+        let count = if whole_stack { u32::MAX } else { 1 };
+        let Some(stack) = inventory.take(selected, count) else {
+            continue;
+        };
+        info!("Inventory: dropped {} {}", stack.count, stack.item);
+        let forward = camera.forward().as_vec3();
+        commands.spawn((
+            WorldItem { stack },
+            Transform::from_translation(camera.translation() + forward * DROP_DISTANCE)
+                .with_rotation(camera.rotation()),
+            LinearVelocity(player_velocity.0 + forward * DROP_SPEED),
+        ));
+    }
+}
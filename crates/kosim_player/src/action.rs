@@ -1,15 +1,18 @@
 use avian3d::prelude::{
-    Collider, ConstantForce, Forces, ShapeHits, WriteRigidBodyForces, forces::ForcesItem,
+    Collider, ConstantForce, Forces, LinearVelocity, Mass, ShapeCastConfig, ShapeHits,
+    SpatialQuery, WriteRigidBodyForces, forces::ForcesItem,
 };
 use bevy::{
     ecs::{
         entity::Entity,
+        message::MessageWriter,
         query::With,
         system::{Query, Res, ResMut},
     },
     input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
     log::{info, trace},
-    math::Vec3,
+    math::{Dir3, Quat, Vec3},
+    transform::components::Transform,
 };
 
 use bevy_enhanced_input::prelude::InputAction;
@@ -18,12 +21,122 @@ use kosim_utility::format_value::{format_value_f32, format_value_vec3};
 
 use crate::{
     Player, PlayerControlConfig,
-    body::{Body, IgnoreRayCollision, StandingSpringForce, compute_ray_length},
+    body::{
+        Body, GROUND_PROBE_RADIUS, IgnoreRayCollision, StandingSpringForce, VoxelGround,
+        compute_ray_length,
+    },
+    config::CrouchMode,
+    gravity::{PlanetGravity, up_at},
+    layers::player_query_filter,
+    locomotion::{Locomotion, LocomotionChanged, LocomotionState},
     motion::Motion,
+    platform::GroundMotion,
+    stamina::Stamina,
     stance::{Stance, StanceType},
 };
 
 //** -- JUMPING LOGIC -- */
+/// Timers that make jumping forgiving and let its height depend on how long jump
+/// is held. See the `coyote_time`, `jump_buffer_time` and `jump_hold_*` fields of
+/// [`PlayerControlConfig`].
+#[derive(Component, Default)]
+pub struct JumpControl {
+    /// Time left to jump after leaving the ground without jumping.
+    pub coyote_timer: f32,
+    /// Time left for a press to still count when the player becomes able to jump.
+    pub buffer_timer: f32,
+    /// Seconds since the current jump started.
+    pub jump_time: f32,
+    /// Jump has been held since the current jump started.
+    pub held: bool,
+    /// The body's speed along `up` this tick, before any jump is applied.
+    pub radial_speed: f32,
+    /// Jump was pressed on a frame since the last fixed tick; set by
+    /// [`latch_jump_press`] and taken by [`update_jump_control`].
+    pub requested: bool,
+    /// Jump was pressed since the previous fixed tick. Read this in `FixedUpdate`
+    /// instead of the action's `just_pressed`, which only lasts one frame.
+    pub pressed: bool,
+}
+
+/// Latch jump presses every frame. `just_pressed` is cleared each frame, and a
+/// frame can pass with no fixed tick, so reading it in `FixedUpdate` drops presses.
+pub fn latch_jump_press(
+    mut player_query: Query<&mut JumpControl, With<Player>>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+) {
+    if !bindings
+        .action_jump
+        .just_pressed(&keys, gamepad_query.single().ok())
+    {
+        return;
+    }
+    for mut jump in &mut player_query {
+        jump.requested = true;
+    }
+}
+
+/// Tick the jump timers from the latched jump press, and shape a jump in progress:
+/// holding jump keeps pushing up on a falling-off curve, and letting go early cuts
+/// the rise short.
+pub fn update_jump_control(
+    mut player_query: Query<
+        (
+            &mut JumpControl,
+            &mut LinearVelocity,
+            &Transform,
+            &Stance,
+            &Locomotion,
+        ),
+        With<Player>,
+    >,
+    player_config: Res<PlayerControlConfig>,
+    gravity: Res<PlanetGravity>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    time: Res<Time>,
+) {
+    let held = bindings
+        .action_jump
+        .pressed(&keys, gamepad_query.single().ok());
+    let dt = time.delta_secs();
+    for (mut jump, mut linear_velocity, transform, stance, locomotion) in &mut player_query {
+        jump.pressed = std::mem::take(&mut jump.requested);
+        if jump.pressed {
+            jump.buffer_timer = player_config.jump_buffer_time;
+        } else {
+            jump.buffer_timer = f32::max(jump.buffer_timer - dt, 0.0);
+        }
+        if stance.current == StanceType::Standing {
+            jump.coyote_timer = player_config.coyote_time;
+        } else {
+            jump.coyote_timer = f32::max(jump.coyote_timer - dt, 0.0);
+        }
+
+        let up = up_at(transform.translation, gravity.center);
+        jump.radial_speed = linear_velocity.0.dot(up);
+        if locomotion.current != LocomotionState::Jump || !jump.held {
+            continue;
+        }
+        jump.jump_time += dt;
+        if held && jump.jump_time < player_config.jump_hold_time {
+            let falloff = 1.0 - jump.jump_time / player_config.jump_hold_time;
+            linear_velocity.0 += up * player_config.jump_hold_acceleration * falloff * dt;
+        } else if !held {
+            jump.held = false;
+            if jump.radial_speed > 0.0 {
+                linear_velocity.0 -=
+                    up * jump.radial_speed * (1.0 - player_config.jump_release_velocity_factor);
+            }
+        }
+    }
+}
+
+/// Jump when a (buffered) jump press meets ground contact, or coyote time after
+/// walking off an edge.
 pub fn detect_action_jumping(
     mut player_query: Query<
         (
@@ -33,14 +146,22 @@ pub fn detect_action_jumping(
             &mut ConstantForce,
             &Motion,
             &mut Stance,
+            &mut Locomotion,
+            &mut JumpControl,
+            &mut Stamina,
+            &Mass,
             &Body,
             &ShapeHits,
+            &VoxelGround,
+            &GroundMotion,
+            &Transform,
         ),
         With<Player>,
     >,
     ignored_entities: Query<Entity, With<IgnoreRayCollision>>,
     player_config: Res<PlayerControlConfig>,
-    keys: Res<ButtonInput<KeyCode>>,
+    gravity: Res<PlanetGravity>,
+    mut ev_locomotion: MessageWriter<LocomotionChanged>,
 ) {
     let (
         entity,
@@ -49,42 +170,82 @@ pub fn detect_action_jumping(
         mut constant_force,
         motion,
         mut stance,
+        mut locomotion,
+        mut jump,
+        mut stamina,
+        mass,
         body,
         ray_hits,
+        voxel_ground,
+        ground,
+        transform,
     ) = player_query.single_mut().expect("We do some errors");
-    if stance.current == StanceType::Standing
-        && keys.pressed(KeyCode::Space)
-        && stance.lockout_timer <= 0.0
+    if jump.buffer_timer <= 0.0 {
+        return;
+    }
+    let grounded = matches!(stance.current, StanceType::Standing | StanceType::Landing);
+    let coyote = locomotion.current == LocomotionState::Fall && jump.coyote_timer > 0.0;
+    if !grounded && !coyote {
+        return;
+    }
+    if !locomotion.current.can_transition_to(LocomotionState::Jump)
+        || (locomotion.is_locked_out() && !locomotion.current.is_airborne())
+        || !stamina.try_spend(player_config.jump_stamina_cost, &player_config)
     {
-        let ray_length: f32 = compute_ray_length(entity, ignored_entities, ray_hits);
-        stance.lockout_timer = player_config.stance_lockout;
-        stance.current = StanceType::Airborne;
-        constant_force.y = 0.0;
-
-        apply_jump_force(
-            &mut forces,
-            &player_config,
-            ray_length,
-            &mut stance,
-            &mut standing_spring,
-            &motion,
-            &body,
+        // Keep the press buffered; it may still go through once the player can jump.
+        return;
+    }
+    // Apply the stance cooldown now that we are jumping. Refused while a lockout
+    // is still running or from a state that cannot jump.
+    let Ok(from) =
+        locomotion.try_transition(LocomotionState::Jump, Some(player_config.stance_lockout))
+    else {
+        return;
+    };
+    ev_locomotion.write(LocomotionChanged {
+        entity,
+        from,
+        to: LocomotionState::Jump,
+    });
+    jump.buffer_timer = 0.0;
+    jump.coyote_timer = 0.0;
+    jump.jump_time = 0.0;
+    jump.held = true;
+
+    let ray_length: f32 = compute_ray_length(entity, ignored_entities, ray_hits, voxel_ground);
+    stance.current = StanceType::Airborne;
+    constant_force.y = 0.0;
+
+    if coyote && jump.radial_speed < 0.0 {
+        // Already falling: cancel the fall so a coyote jump rises as high as any other.
+        forces.apply_local_linear_impulse((Vec3::Y * -jump.radial_speed * mass.0).into());
+    } else if ground.entity.is_some() {
+        // Take off from the body's radial speed, so jumping off a rising lift
+        // carries its momentum and one off a falling lift does not launch higher.
+        let up = up_at(transform.translation, gravity.center);
+        let ground_radial = ground.velocity.dot(up);
+        forces.apply_local_linear_impulse(
+            (Vec3::Y * (ground_radial - jump.radial_speed) * mass.0).into(),
         );
     }
+    apply_jump_force(
+        &mut forces,
+        &player_config,
+        ray_length,
+        &mut standing_spring,
+        &motion,
+        &body,
+    );
 }
 
 pub fn apply_jump_force(
     forces: &mut ForcesItem<'_, '_>,
     player_config: &Res<PlayerControlConfig>,
     ray_length: f32,
-    stance: &mut Stance,
     standing_spring: &mut StandingSpringForce,
     _motion: &Motion,
     body: &Body,
 ) {
-    // Apply the stance cooldown now that we are jumping.
-    stance.lockout_timer = player_config.stance_lockout;
-
     let half_jump_strength: f32 = player_config.jump_strength / 2.0;
     let clamped_jump_force: f32 =
         compute_clamped_jump_force_factor(&body, &standing_spring, ray_length);
@@ -156,13 +317,13 @@ pub struct Sprint;
 
 // This is the system that detects if the player is sprinting and updates the motion.sprinting flag accordingly.
 pub fn detect_action_sprinting(
-    mut player_query: Query<(&mut Motion, &Stance), With<Player>>,
+    mut player_query: Query<(&mut Motion, &Locomotion, &Stamina), With<Player>>,
     gamepad_query: Query<(Entity, &Gamepad)>,
     keys: Res<ButtonInput<KeyCode>>,
     bindings: Res<Bindings>,
 ) {
-    for (mut motion, stance) in player_query.iter_mut() {
-        if stance.current == StanceType::Airborne {
+    for (mut motion, locomotion, stamina) in player_query.iter_mut() {
+        if locomotion.current.is_airborne() {
             return;
         }
 
@@ -173,6 +334,11 @@ pub fn detect_action_sprinting(
                 motion.sprinting = gamepad.pressed(bindings.action_sprint.button);
             }
         }
+
+        // Too winded to run: walk until stamina recovers.
+        if stamina.exhausted {
+            motion.sprinting = false;
+        }
     }
 }
 
@@ -181,55 +347,110 @@ pub fn detect_action_sprinting(
 #[action_output(bool)]
 pub struct Crouch;
 
+/// Radius of the sphere swept upward to check for headroom before standing up. A
+/// little narrower than the capsule so walls beside the player don't count.
+const CEILING_PROBE_RADIUS: f32 = GROUND_PROBE_RADIUS * 0.9;
+
+/// Whether the player can stand up from a crouch: sweeps a sphere from the body's
+/// origin up through the space the standing capsule (raised to the standing ride
+/// height) would occupy.
+fn has_standing_headroom(
+    spatial_query: &SpatialQuery,
+    entity: Entity,
+    transform: &Transform,
+    up: Vec3,
+    player_config: &PlayerControlConfig,
+) -> bool {
+    let Ok(direction) = Dir3::new(up) else {
+        return true;
+    };
+    let ride_height_gain = player_config.ride_height * (1.0 - player_config.crouched_height_factor);
+    let distance = ride_height_gain + player_config.capsule_height * 0.5;
+    spatial_query
+        .cast_shape(
+            &Collider::sphere(CEILING_PROBE_RADIUS),
+            transform.translation,
+            Quat::IDENTITY,
+            direction,
+            &ShapeCastConfig::from_max_distance(distance),
+            &player_query_filter(entity),
+        )
+        .is_none()
+}
+
+/// Read the crouch input ([`CrouchMode::Hold`] or [`CrouchMode::Toggle`]) into
+/// [`Stance::crouch_requested`], then move the body toward it: crouching scales the
+/// collider down and lowers the ride height right away, standing up waits until
+/// there is headroom for the full capsule.
 pub fn detect_action_crouching(
-    mut player_query: Query<(&mut Body, &mut Stance), With<Player>>,
-    // The player's capsule collider lives on the Player entity itself (there is no
-    // separate flagged collider entity), so target that.
-    mut player_collider_query: Query<&mut Collider, With<Player>>,
-    player_config: ResMut<PlayerControlConfig>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Body,
+            &mut Stance,
+            &mut StandingSpringForce,
+            &mut Collider,
+        ),
+        With<Player>,
+    >,
+    spatial_query: SpatialQuery,
+    gravity: Res<PlanetGravity>,
+    player_config: Res<PlayerControlConfig>,
     gamepad_query: Query<(Entity, &Gamepad)>,
     keys: Res<ButtonInput<KeyCode>>,
     bindings: Res<Bindings>,
 ) {
-    for (mut body, mut stance) in player_query.iter_mut() {
-        let mut pressed: bool = false;
-        if let Ok((_entity, gamepad)) = gamepad_query.single() {
-            if gamepad.just_pressed(bindings.action_toggle_crouched.button)
-                || keys.just_pressed(bindings.action_toggle_crouched.key)
-            {
-                pressed = true;
+    // The player's capsule collider lives on the Player entity itself (there is no
+    // separate flagged collider entity).
+    for (entity, transform, mut body, mut stance, mut standing_spring_force, mut collider) in
+        player_query.iter_mut()
+    {
+        let gamepad = gamepad_query
+            .single()
+            .ok()
+            .map(|(_entity, gamepad)| gamepad);
+        match player_config.crouch_mode {
+            CrouchMode::Hold => {
+                stance.crouch_requested = keys.pressed(bindings.action_toggle_crouched.key)
+                    || gamepad.is_some_and(|g| g.pressed(bindings.action_toggle_crouched.button));
             }
-        } else {
-            if keys.just_pressed(bindings.action_toggle_crouched.key) {
-                pressed = true;
+            CrouchMode::Toggle => {
+                if keys.just_pressed(bindings.action_toggle_crouched.key)
+                    || gamepad
+                        .is_some_and(|g| g.just_pressed(bindings.action_toggle_crouched.button))
+                {
+                    stance.crouch_requested = !stance.crouch_requested;
+                }
             }
         }
 
-        if !pressed {
-            return;
+        if stance.crouch_requested == stance.crouched {
+            continue;
+        }
+        if !stance.crouch_requested {
+            let up = up_at(transform.translation, gravity.center);
+            if !has_standing_headroom(&spatial_query, entity, transform, up, &player_config) {
+                // Stay crouched; we stand as soon as the ceiling is clear.
+                trace!("Crouch: no headroom to stand up");
+                continue;
+            }
         }
 
-        let Ok(mut collider) = player_collider_query.single_mut() else {
-            return;
-        };
-
-        // Toggle crouching flag
-        stance.crouched = !stance.crouched;
-
-        if stance.crouched == true {
+        stance.crouched = stance.crouch_requested;
+        if stance.crouched {
             // Update the collider scale
             let crouched_height: f32 =
                 player_config.capsule_height * player_config.crouched_height_factor;
             collider.set_scale(Vec3::from([1.0, crouched_height, 1.0]), 10);
-            // stance.ride_height.target =
-            //     player_config.ride_height * player_config.crouched_height_factor;
             body.current_body_height = crouched_height;
         } else {
             // Reset the collider scale to One
             collider.set_scale(Vec3::from([1.0, 1.0, 1.0]), 10);
-            //stance.ride_height.target = player_config.ride_height;
             body.current_body_height = player_config.capsule_height;
         }
+        // The ride spring eases toward the new height (see `apply_standing_spring_force`).
+        standing_spring_force.length.target = player_config.standing_ride_height(stance.crouched);
 
         info!(
             "Updated: Crouched -> {}, Collider scaled to: {:?}",
@@ -271,9 +492,9 @@ pub struct FootstepEvent {
 // This is the time in seconds between each footstep. When sprinting this value is multiplied.
 pub const ACTION_STEP_DELTA_DEFAULT: f32 = 0.64;
 const LOCKIN_ACTION_THRESHOLD_PERCENTAGE: f32 = 0.1;
-const _BUMP_ACTION_THRESHOLD_PERCENTAGE: f32 = 0.70;
-const _BUMP_REMAINING_ACTION_STEP: f32 =
-    ACTION_STEP_DELTA_DEFAULT * (1.0 - _BUMP_ACTION_THRESHOLD_PERCENTAGE);
+const BUMP_ACTION_THRESHOLD_PERCENTAGE: f32 = 0.70;
+const BUMP_REMAINING_ACTION_STEP: f32 =
+    ACTION_STEP_DELTA_DEFAULT * (1.0 - BUMP_ACTION_THRESHOLD_PERCENTAGE);
 const LOCKIN_ACTION_STEP_DELTA: f32 =
     ACTION_STEP_DELTA_DEFAULT * (1.0 - LOCKIN_ACTION_THRESHOLD_PERCENTAGE);
 
@@ -332,6 +553,7 @@ pub fn load_footstep_sfx(mut commands: Commands, asset_server: Res<AssetServer>)
 pub const DEFAULT_STEP_VOLUME: f32 = -12.0;
 pub const UNSIGNED_STEP_VOLUME_SPRINT_BONUS: f32 = 2.0;
 pub const UNSIGNED_STEP_VOLUME_UNMOVING_PENALTY: f32 = 4.0;
+pub const UNSIGNED_STEP_VOLUME_FATIGUE_BONUS: f32 = 3.0;
 
 // todo: move this somewhere more appropriate.
 // ! This should ideally not take in and load a new sound ever time and should be loaded once. ALSO, remove the inability to iterate over all the events this should be solved with an update.
@@ -367,7 +589,16 @@ pub fn play_footstep_sfx(
 
 pub fn tick_footstep(
     mut ev_footstep: MessageWriter<FootstepEvent>,
-    mut query: Query<(&mut ActionStep, &mut Stance, &Motion), With<Player>>,
+    mut query: Query<
+        (
+            &mut ActionStep,
+            &Locomotion,
+            &Motion,
+            &Stamina,
+            &mut StandingSpringForce,
+        ),
+        With<Player>,
+    >,
     // mut camera_query: Query<
     //     (&mut Transform, &mut SmoothedCamera),
     //     (With<Camera3d>, Without<Player>),
@@ -376,9 +607,9 @@ pub fn tick_footstep(
     config: Res<PlayerControlConfig>,
     time: Res<Time>,
 ) {
-    for (mut action, stance, motion) in query.iter_mut() {
+    for (mut action, locomotion, motion, stamina, mut standing_spring_force) in query.iter_mut() {
         // you must be on the ground for this sound to play.
-        if stance.current != StanceType::Standing && stance.current != StanceType::Landing {
+        if !locomotion.current.is_grounded() {
             continue;
         }
         // if you are not moving and need to take more than 85% of your remaining step we play no sound.
@@ -394,14 +625,14 @@ pub fn tick_footstep(
 
         // info!("Step Speed Scale: {}", step_speed_scale);
 
-        let mut _ride_height_offset: f32 = ternary!(
+        let mut ride_height_offset: f32 = ternary!(
             motion.sprinting,
             config.ride_height_step_offset,
             -config.ride_height_step_offset
         );
 
         if motion.sprinting == true || motion.moving == false {
-            _ride_height_offset *= 1.4; // this is kinda arbitrary. but this little bit of kick is applied when you start sprinting from a stand still.
+            ride_height_offset *= 1.4; // this is kinda arbitrary. but this little bit of kick is applied when you start sprinting from a stand still.
         }
 
         // reduce the time by elaspsed times the scale.
@@ -414,30 +645,19 @@ pub fn tick_footstep(
         if motion.sprinting {
             vol += UNSIGNED_STEP_VOLUME_SPRINT_BONUS;
         }
-
-        // bump the riding height when the delta is less than the bump threshold.
-        // todo: this needs to be moved to another component that handles view bobbing separately.
-        // if config.enable_view_bobbing
-        //     && action.delta <= BUMP_REMAINING_ACTION_STEP
-        //     && action.bumped == false
-        // {
-        //     // ! DISABLING HEADBOB RIGHT NOW
-        //     // stance.ride_height.current =
-        //     //     config.ride_height + (ride_height_offset * current_ride_height_offset_scaler);
-        //     action.bumped = true;
-        //     let (camera_transform, mut _smoothed_camera) = camera_query.single_mut().unwrap();
-        //     let (_yaw, _pitch, _) = camera_transform.rotation.to_euler(EulerRot::default());
-        //     let dir: f32 = ternary!(action.dir == FootstepDirection::Left, 1.0, -1.0);
-        //     let sprinting_scale: f32 = ternary!(motion.sprinting, 0.2, 0.15);
-        //     let roll: f32 = dir * ROTATION_AMOUNT.to_radians() * sprinting_scale;
-        //     // smoothed_camera.lean.target = Vec3::from_array([yaw, pitch, roll]);
-        //     // smoothed_camera.lock_lean = LEAN_LOCKOUT_TIME;
-        //     // info!(
-        //     //     "Leaning: {}, with roll: {}",
-        //     //     format_value_vec3(_smoothed_camera.lean.current, Some(2), true),
-        //     //     format_value_f32(roll, Some(6), true)
-        //     // );
-        // }
+        // Tired feet land heavier.
+        vol += UNSIGNED_STEP_VOLUME_FATIGUE_BONUS * stamina.fatigue(&config);
+
+        // bump the riding height when the delta is less than the bump threshold. Only the
+        // current length is nudged; the spring eases it back to its target, so the body
+        // (and the camera riding on it) dips and recovers once per step.
+        if config.enable_view_bobbing
+            && action.delta <= BUMP_REMAINING_ACTION_STEP
+            && action.bumped == false
+        {
+            standing_spring_force.length.current += ride_height_offset;
+            action.bumped = true;
+        }
 
         // if the inter step delta has elapsed increase the delta, flip the dir, reset the bump, and queue the sound event.
         if action.delta <= 0.0 {
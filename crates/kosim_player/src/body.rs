@@ -13,6 +13,7 @@ use bevy::{
     transform::components::Transform,
     time::Time,
 };
+use kosim_world::ChunkManager;
 use kosim_utility::{
     exp_decay,
     format_value::{format_value_vec3},
@@ -60,10 +61,51 @@ pub struct IgnoreRayCollision;
 /// footprint, so the spring floats the capsule clear of every surface below it.
 pub const GROUND_PROBE_RADIUS: f32 = 0.5;
 
+/// How far below the player the voxel-field ground probe looks.
+const VOXEL_GROUND_PROBE_DISTANCE: f32 = 8.0;
+
+/// Distance from the player's origin to the voxel surface straight below it, read
+/// from the voxel field rather than from colliders (see [`probe_voxel_ground`]).
+#[derive(Component)]
+pub struct VoxelGround {
+    pub distance: f32,
+}
+
+impl Default for VoxelGround {
+    fn default() -> Self {
+        Self {
+            distance: f32::INFINITY,
+        }
+    }
+}
+
+/// Ray-march the voxel field radially below the player. This needs no collider, so
+/// the ride spring keeps holding the player up over chunks whose collider is still
+/// queued (or was never built) instead of letting them drop through.
+pub fn probe_voxel_ground(
+    mut query: Query<(&Transform, &mut VoxelGround), With<Player>>,
+    chunks: Option<Res<ChunkManager>>,
+    gravity: Res<PlanetGravity>,
+) {
+    for (transform, mut ground) in &mut query {
+        let Some(chunks) = chunks.as_ref() else {
+            ground.distance = f32::INFINITY;
+            continue;
+        };
+        let up = up_at(transform.translation, gravity.center);
+        ground.distance = chunks
+            .world()
+            .raycast(transform.translation, -up, VOXEL_GROUND_PROBE_DISTANCE)
+            .map(|hit| hit.distance)
+            .unwrap_or(f32::INFINITY);
+    }
+}
+
 pub fn compute_ray_length(
     entity: Entity,
     entities_to_ignore: Query<Entity, With<IgnoreRayCollision>>,
     shape_hits: &ShapeHits,
+    voxel_ground: &VoxelGround,
 ) -> f32 {
     // Compute the ray_length to a hit, if we don't hit anything we assume the ground is infinitly far away.
     let mut ray_length: f32 = f32::INFINITY;
@@ -91,7 +133,8 @@ pub fn compute_ray_length(
             break;
         }
     }
-    ray_length
+    // The voxel field is the ground truth; it also covers chunks with no collider yet.
+    ray_length.min(voxel_ground.distance)
 }
 
 pub fn apply_standing_spring_force(
@@ -103,6 +146,7 @@ pub fn apply_standing_spring_force(
         &mut StandingSpringForce,
         &Mass,
         &ShapeHits,
+        &VoxelGround,
     )>,
     config: Res<PlayerControlConfig>,
     gravity: Res<PlanetGravity>,
@@ -117,10 +161,11 @@ pub fn apply_standing_spring_force(
         mut standing_spring_force,
         mass,
         ray_hits,
+        voxel_ground,
     ) in &mut query
     {
         // Distance to the ground along the (radial) probe; infinite if nothing hit.
-        let ray_length: f32 = compute_ray_length(entity, ignored_entities, ray_hits);
+        let ray_length: f32 = compute_ray_length(entity, ignored_entities, ray_hits, voxel_ground);
 
         // Lerp current_ride_height to target_ride_height, this target_ride_height changes depending on the stance. Standing, Crouching, and Prone.
         standing_spring_force.length.current = exp_decay::<f32>(
@@ -0,0 +1,289 @@
+//! Picking up, carrying and throwing physics objects.
+//!
+//! Interacting with a focused dynamic body (an [`Interactable`] with
+//! [`InteractVerb::PickUp`], or any dynamic body without an `Interactable`) picks it
+//! up if it is no heavier than [`PlayerControlConfig::carry_max_mass`]. While
+//! [`Carried`] it is pulled toward a point in front of the camera by a damped
+//! spring whose force is capped, so heavy objects lag and swing where light ones
+//! snap into place. It moves on the [`crate::layers::GameLayer::Carried`] layer: it
+//! still collides with terrain chunks and props, but not the player.
+//!
+//! Holding the rotate key turns the object with the mouse (the camera holds still),
+//! interact drops it, and throw launches it along the view. It is also dropped when
+//! it snags on something and falls too far behind its hold point.
+
+use avian3d::prelude::{
+    AngularVelocity, CollisionLayers, ComputedMass, LinearVelocity, RigidBody, SweptCcd,
+};
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageReader,
+        query::{Has, With, Without},
+        system::{Commands, Query, Res},
+    },
+    input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
+    log::info,
+    math::{Quat, Vec3},
+    time::Time,
+    transform::components::GlobalTransform,
+};
+use kosim_camera::{
+    GameCamera,
+    third_person_camera::{ThirdPersonCamera, camera_reach_offset},
+};
+use kosim_input::{binding::Bindings, input::Input};
+
+use crate::{
+    Player,
+    config::PlayerControlConfig,
+    focus::{FOCUS_DISTANCE, FocusTarget},
+    health::Dead,
+    interact::{InteractEvent, InteractVerb, Interactable},
+    layers::carried_layers,
+};
+
+/// The body the player is carrying.
+#[derive(Component)]
+pub struct Carrying {
+    pub target: Entity,
+    /// Distance in front of the camera the body is held at, less the camera's
+    /// [`camera_reach_offset`] so it stays put across camera modes.
+    pub distance: f32,
+    /// The body's rotation relative to the camera.
+    pub rotation: Quat,
+}
+
+/// A body being carried.
+#[derive(Component)]
+pub struct Carried {
+    pub holder: Entity,
+    /// The layers to restore when it is let go.
+    previous_layers: Option<CollisionLayers>,
+}
+
+/// Run condition: mouse look turns the camera unless it is rotating a carried body.
+pub fn player_not_rotating_carried(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    carrying: Query<(), (With<Player>, With<Carrying>)>,
+) -> bool {
+    carrying.is_empty() || !keys.pressed(bindings.action_rotate_carried)
+}
+
+fn pick_up(
+    commands: &mut Commands,
+    player: Entity,
+    target: Entity,
+    camera: &GlobalTransform,
+    reach_offset: f32,
+    body: &GlobalTransform,
+    layers: Option<&CollisionLayers>,
+    config: &PlayerControlConfig,
+) {
+    let distance = (camera.translation().distance(body.translation()) - reach_offset)
+        .clamp(config.carry_min_distance, FOCUS_DISTANCE);
+    commands.entity(player).insert(Carrying {
+        target,
+        distance,
+        rotation: camera.rotation().inverse() * body.rotation(),
+    });
+    commands.entity(target).insert((
+        Carried {
+            holder: player,
+            previous_layers: layers.copied(),
+        },
+        carried_layers(),
+        // Thrown or swung fast, it must still not tunnel through the terrain.
+        SweptCcd::default(),
+    ));
+    info!("Carry: PICKED UP {}", target);
+}
+
+fn release(commands: &mut Commands, player: Entity, target: Entity, carried: Option<&Carried>) {
+    commands.entity(player).remove::<Carrying>();
+    let Ok(mut target_commands) = commands.get_entity(target) else {
+        return;
+    };
+    target_commands.remove::<(Carried, SweptCcd)>();
+    match carried.and_then(|carried| carried.previous_layers) {
+        Some(layers) => {
+            target_commands.insert(layers);
+        }
+        None => {
+            target_commands.remove::<CollisionLayers>();
+        }
+    }
+    info!("Carry: RELEASED {}", target);
+}
+
+/// Pick up, drop and throw from the interact and throw actions.
+pub fn grab_or_release(
+    players: Query<(Entity, Option<&Carrying>, Has<Dead>), With<Player>>,
+    focused: Query<(Entity, &RigidBody, Has<Interactable>), With<FocusTarget>>,
+    mut bodies: Query<
+        (
+            &RigidBody,
+            &ComputedMass,
+            &GlobalTransform,
+            &mut LinearVelocity,
+            Option<&CollisionLayers>,
+            Option<&Carried>,
+        ),
+        Without<Player>,
+    >,
+    camera_query: Query<(&GlobalTransform, Option<&ThirdPersonCamera>), With<GameCamera>>,
+    config: Res<PlayerControlConfig>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut ev_interact: MessageReader<InteractEvent>,
+    mut commands: Commands,
+) {
+    let gamepad = gamepad_query.single().ok();
+    let interact = bindings.action_interact.just_pressed(&keys, gamepad);
+    let throw = bindings.action_throw.just_pressed(&keys, gamepad);
+    let events: Vec<InteractEvent> = ev_interact.read().cloned().collect();
+    let Ok((camera, third_person)) = camera_query.single() else {
+        return;
+    };
+    let reach_offset = camera_reach_offset(third_person);
+
+    for (player, carrying, dead) in &players {
+        if let Some(carrying) = carrying {
+            let target = carrying.target;
+            let carried = bodies.get(target).ok().and_then(|body| body.5);
+            if dead || interact {
+                release(&mut commands, player, target, carried);
+            } else if throw {
+                release(&mut commands, player, target, carried);
+                if let Ok((_, mass, _, mut velocity, _, _)) = bodies.get_mut(target) {
+                    let speed = (config.carry_throw_impulse / mass.value().max(f32::EPSILON))
+                        .min(config.carry_throw_max_speed);
+                    velocity.0 += camera.forward().as_vec3() * speed;
+                    info!("Carry: THROWN at {:.1} units/s", speed);
+                }
+            }
+            continue;
+        }
+        if dead {
+            continue;
+        }
+
+        // A pick-up verb, or a plain interact on a dynamic body with no verb of its own.
+        let target = events
+            .iter()
+            .find(|event| event.player == player && event.verb == InteractVerb::PickUp)
+            .map(|event| event.target)
+            .or_else(|| {
+                interact
+                    .then(|| {
+                        focused
+                            .iter()
+                            .find(|(_, body, interactable)| body.is_dynamic() && !interactable)
+                            .map(|(entity, _, _)| entity)
+                    })
+                    .flatten()
+            });
+        let Some(target) = target else {
+            continue;
+        };
+        let Ok((rigid_body, mass, transform, _, layers, carried)) = bodies.get(target) else {
+            continue;
+        };
+        if !rigid_body.is_dynamic() || carried.is_some() {
+            continue;
+        }
+        if mass.value() > config.carry_max_mass {
+            info!("Carry: {} is too heavy ({:.1})", target, mass.value());
+            continue;
+        }
+        if camera.translation().distance(transform.translation()) > FOCUS_DISTANCE + reach_offset {
+            continue;
+        }
+        pick_up(
+            &mut commands,
+            player,
+            target,
+            camera,
+            reach_offset,
+            transform,
+            layers,
+            &config,
+        );
+    }
+}
+
+/// Pull carried bodies toward their hold point in front of the camera and turn
+/// them to their held rotation.
+pub fn drive_carried(
+    mut players: Query<(Entity, &mut Carrying, &LinearVelocity), With<Player>>,
+    mut bodies: Query<
+        (
+            &ComputedMass,
+            &GlobalTransform,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            Option<&Carried>,
+        ),
+        Without<Player>,
+    >,
+    camera_query: Query<(&GlobalTransform, Option<&ThirdPersonCamera>), With<GameCamera>>,
+    config: Res<PlayerControlConfig>,
+    input: Res<Input>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let Ok((camera, third_person)) = camera_query.single() else {
+        return;
+    };
+    let reach_offset = camera_reach_offset(third_person);
+    let dt = time.delta_secs();
+    for (player, mut carrying, player_velocity) in &mut players {
+        let Ok((mass, transform, mut velocity, mut angular_velocity, carried)) =
+            bodies.get_mut(carrying.target)
+        else {
+            // Despawned while carried.
+            commands.entity(player).remove::<Carrying>();
+            continue;
+        };
+
+        let target =
+            camera.translation() + camera.forward().as_vec3() * (carrying.distance + reach_offset);
+        let error = target - transform.translation();
+        if error.length() > config.carry_break_distance {
+            info!("Carry: SNAGGED");
+            release(&mut commands, player, carrying.target, carried);
+            continue;
+        }
+
+        // A critically damped spring toward the hold point, relative to the player so
+        // it keeps up while walking, with the force capped so mass matters.
+        let stiffness = config.carry_stiffness;
+        let damping = 2.0 * stiffness.sqrt();
+        let relative_velocity = velocity.0 - player_velocity.0;
+        let mass = mass.value().max(f32::EPSILON);
+        let force = ((error * stiffness - relative_velocity * damping) * mass)
+            .clamp_length_max(config.carry_max_force);
+        velocity.0 += force / mass * dt;
+
+        if keys.pressed(bindings.action_rotate_carried) {
+            let yaw = Quat::from_rotation_y(input.focus_delta_raw.x.to_radians());
+            let pitch = Quat::from_rotation_x(input.focus_delta_raw.y.to_radians());
+            carrying.rotation = (yaw * pitch * carrying.rotation).normalize();
+        }
+        let desired = camera.rotation() * carrying.rotation;
+        let (axis, mut angle) = (desired * transform.rotation().inverse()).to_axis_angle();
+        if angle > std::f32::consts::PI {
+            angle -= std::f32::consts::TAU;
+        }
+        angular_velocity.0 = if axis.is_finite() {
+            axis * angle * config.carry_rotation_stiffness
+        } else {
+            Vec3::ZERO
+        };
+    }
+}
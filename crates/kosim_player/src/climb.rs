@@ -0,0 +1,271 @@
+//! Ladders and climbable surfaces.
+//!
+//! Entities with [`Climbable`] (ladders) and terrain of a climbable
+//! [`VoxelMaterial`] (vines) can be climbed: walking into one within
+//! [`PlayerControlConfig::climb_reach`] attaches the player in
+//! [`LocomotionState::Climb`]. While attached, gravity and the ride spring are held
+//! off and the movement input drives the player along the surface: forward/back
+//! climbs along the radial up projected onto the surface, left/right moves across
+//! it. The body itself stays radially aligned by `player_rotation_system`.
+//!
+//! Climbing drains stamina; running out, climbing off the top or bottom, or
+//! pressing jump (which pushes off the surface) detaches the player.
+
+use avian3d::prelude::{
+    Collider, ConstantForce, LinearVelocity, ShapeCastConfig, SpatialQuery, SpatialQueryFilter,
+};
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageWriter,
+        query::{Has, With, Without},
+        system::{Commands, Query, Res},
+    },
+    log::info,
+    math::{Dir3, Quat, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+use kosim_input::input::Input;
+use kosim_world::{ChunkManager, voxel::VoxelMaterial};
+
+use crate::{
+    Player,
+    action::JumpControl,
+    body::GROUND_PROBE_RADIUS,
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+    layers::player_query_filter,
+    ledge::Mantling,
+    locomotion::{Locomotion, LocomotionChanged, LocomotionState},
+    slope::slope_angle,
+    stamina::Stamina,
+    stance::{Stance, StanceType},
+};
+
+/// Radius of the sphere swept toward a surface to find something to climb.
+const CLIMB_PROBE_RADIUS: f32 = 0.2;
+/// Speed at which an attached player is held against the surface.
+const CLIMB_STICK_SPEED: f32 = 0.5;
+/// Seconds after letting go before the player can grab on again, so pushing off
+/// doesn't re-attach straight away.
+const CLIMB_REGRAB_DELAY: f32 = 0.3;
+
+/// Marks an entity (a ladder, a trellis, ...) that the player can climb.
+#[derive(Component, Default)]
+pub struct Climbable;
+
+/// The player is attached to a climbable surface.
+#[derive(Component)]
+pub struct Climbing {
+    /// Outward normal of the surface being climbed.
+    pub normal: Vec3,
+}
+
+/// Sweep from `origin` along `direction` for something climbable, returning its
+/// outward surface normal: either a [`Climbable`] collider or a climbable voxel.
+fn probe_climbable(
+    spatial_query: &SpatialQuery,
+    filter: &SpatialQueryFilter,
+    climbables: &Query<(), With<Climbable>>,
+    chunks: Option<&ChunkManager>,
+    origin: Vec3,
+    direction: Vec3,
+    reach: f32,
+) -> Option<Vec3> {
+    let dir = Dir3::new(direction).ok()?;
+    let distance = GROUND_PROBE_RADIUS + reach;
+    if let Some(hit) = spatial_query.cast_shape(
+        &Collider::sphere(CLIMB_PROBE_RADIUS),
+        origin,
+        Quat::IDENTITY,
+        dir,
+        &ShapeCastConfig::from_max_distance(distance),
+        filter,
+    ) && climbables.contains(hit.entity)
+    {
+        return Some(-hit.normal1);
+    }
+    let hit = chunks?.world().raycast(origin, direction, distance)?;
+    let climbable = chunks?
+        .world()
+        .voxel_material(hit.voxel.x as i64, hit.voxel.y as i64, hit.voxel.z as i64)
+        .is_some_and(VoxelMaterial::is_climbable);
+    climbable.then_some(hit.normal)
+}
+
+/// The direction "up the surface" with outward normal `normal`: the radial up
+/// projected onto the surface plane.
+fn climb_up(normal: Vec3, up: Vec3) -> Vec3 {
+    (up - normal * up.dot(normal)).normalize_or(up)
+}
+
+/// Attach players walking into a climbable surface that is too steep to walk up.
+pub fn detect_climb(
+    mut query: Query<
+        (Entity, &Transform, &mut Locomotion, &Stamina),
+        (With<Player>, Without<Climbing>, Without<Mantling>),
+    >,
+    climbables: Query<(), With<Climbable>>,
+    spatial_query: SpatialQuery,
+    chunks: Option<Res<ChunkManager>>,
+    gravity: Res<PlanetGravity>,
+    config: Res<PlayerControlConfig>,
+    input: Res<Input>,
+    mut commands: Commands,
+    mut ev_locomotion: MessageWriter<LocomotionChanged>,
+) {
+    if input.movement_raw.z <= 0.0 {
+        return;
+    }
+    for (entity, transform, mut locomotion, stamina) in &mut query {
+        if stamina.exhausted
+            || !locomotion.current.can_transition_to(LocomotionState::Climb)
+            || (locomotion.previous == LocomotionState::Climb
+                && locomotion.time_in_state < CLIMB_REGRAB_DELAY)
+        {
+            continue;
+        }
+        let up = up_at(transform.translation, gravity.center);
+        let forward = transform.forward().as_vec3();
+        let forward = (forward - up * forward.dot(up)).normalize_or_zero();
+        let filter = player_query_filter(entity);
+        let Some(normal) = probe_climbable(
+            &spatial_query,
+            &filter,
+            &climbables,
+            chunks.as_deref(),
+            transform.translation,
+            forward,
+            config.climb_reach,
+        ) else {
+            continue;
+        };
+        // Walkable ground is walked on, not climbed.
+        if slope_angle(normal, up) <= config.max_walkable_slope_angle.to_radians() {
+            continue;
+        }
+        let Ok(from) = locomotion.try_transition(LocomotionState::Climb, None) else {
+            continue;
+        };
+        ev_locomotion.write(LocomotionChanged {
+            entity,
+            from,
+            to: LocomotionState::Climb,
+        });
+        info!("Climbing: ATTACHED");
+        commands.entity(entity).insert(Climbing { normal });
+    }
+}
+
+/// Move attached players along their surface, and detach them when they jump off,
+/// run out of stamina, or climb past its top or bottom. Runs after the ride spring
+/// so it can hold gravity off.
+pub fn drive_climb(
+    mut query: Query<
+        (
+            Entity,
+            &mut Climbing,
+            &Transform,
+            &mut LinearVelocity,
+            &mut ConstantForce,
+            &mut Locomotion,
+            &mut Stamina,
+            &mut JumpControl,
+            &Stance,
+            Has<Mantling>,
+        ),
+        With<Player>,
+    >,
+    climbables: Query<(), With<Climbable>>,
+    spatial_query: SpatialQuery,
+    chunks: Option<Res<ChunkManager>>,
+    gravity: Res<PlanetGravity>,
+    config: Res<PlayerControlConfig>,
+    input: Res<Input>,
+    mut commands: Commands,
+    mut ev_locomotion: MessageWriter<LocomotionChanged>,
+    time: Res<Time>,
+) {
+    for (
+        entity,
+        mut climbing,
+        transform,
+        mut linear_velocity,
+        mut constant_force,
+        mut locomotion,
+        mut stamina,
+        mut jump,
+        stance,
+        mantling,
+    ) in &mut query
+    {
+        // A mantle over the top takes over from the climb.
+        if mantling || locomotion.current != LocomotionState::Climb {
+            commands.entity(entity).remove::<Climbing>();
+            continue;
+        }
+        let up = up_at(transform.translation, gravity.center);
+        let surface_up = climb_up(climbing.normal, up);
+        let across = surface_up.cross(climbing.normal);
+
+        let mut detach = None;
+        if jump.pressed {
+            // Push off the surface.
+            linear_velocity.0 =
+                up * config.climb_jump_speed + climbing.normal * config.climb_push_off_speed;
+            jump.buffer_timer = 0.0;
+            jump.jump_time = 0.0;
+            jump.held = false;
+            detach = Some(LocomotionState::Jump);
+        } else if stamina.exhausted {
+            detach = Some(LocomotionState::Fall);
+        } else if input.movement_raw.z < 0.0 && stance.current == StanceType::Standing {
+            // Climbed down onto the ground.
+            detach = Some(LocomotionState::Idle);
+        } else {
+            let filter = player_query_filter(entity);
+            match probe_climbable(
+                &spatial_query,
+                &filter,
+                &climbables,
+                chunks.as_deref(),
+                transform.translation,
+                -climbing.normal,
+                config.climb_reach,
+            ) {
+                Some(normal) => climbing.normal = normal,
+                None => {
+                    if input.movement_raw.z > 0.0 {
+                        // Over the top: carry on up and onto it.
+                        linear_velocity.0 =
+                            (up - climbing.normal).normalize_or(up) * config.climb_speed;
+                    }
+                    detach = Some(LocomotionState::Fall);
+                }
+            }
+        }
+
+        if let Some(next) = detach {
+            if let Ok(from) = locomotion.try_transition(next, None) {
+                ev_locomotion.write(LocomotionChanged {
+                    entity,
+                    from,
+                    to: next,
+                });
+            }
+            info!("Climbing: DETACHED ({})", next);
+            commands.entity(entity).remove::<Climbing>();
+            continue;
+        }
+
+        let climb = (surface_up * input.movement_raw.z + across * input.movement_raw.x)
+            .clamp_length_max(1.0);
+        linear_velocity.0 = climb * config.climb_speed - climbing.normal * CLIMB_STICK_SPEED;
+        constant_force.0 = Vec3::ZERO;
+        if climb.length_squared() > 0.0 {
+            stamina.drain(config.climb_stamina_drain * time.delta_secs(), &config);
+        }
+    }
+}
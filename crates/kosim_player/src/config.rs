@@ -1,7 +1,31 @@
-use bevy::ecs::resource::Resource;
+use bevy::{
+    asset::{Asset, AssetEvent, AssetLoadFailedEvent, AssetServer, Assets, Handle},
+    ecs::{
+        message::MessageReader,
+        query::With,
+        resource::Resource,
+        system::{Commands, Query, Res, ResMut},
+    },
+    log::{error, info},
+    reflect::TypePath,
+};
+use kosim_input::InputConfig;
+use kosim_utility::toml_file::TomlFile;
+use serde::Deserialize;
 
+use crate::{
+    Player, body::StandingSpringForce, health::Health, jetpack::Jetpack, motion::Motion,
+    stamina::Stamina, stance::Stance,
+};
 
-#[derive(Resource)]
+/// Asset path of the player tuning file, relative to `assets/`.
+pub const PLAYER_CONFIG_PATH: &str = "config/player.toml";
+
+/// Player tuning. Starts at [`Default`] and is replaced by [`PLAYER_CONFIG_PATH`]
+/// once it loads; edits to the file are re-applied while the game runs (see
+/// [`apply_player_config`]). Fields missing from the file keep their defaults.
+#[derive(Resource, Asset, TypePath, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PlayerControlConfig {
     pub capsule_height: f32,
     pub ride_height: f32,
@@ -17,11 +41,116 @@ pub struct PlayerControlConfig {
     pub jump_strength: f32,
     pub default_movement_speed: f32,
     pub sprint_speed_factor: f32,
-    pub _movement_decay: f32,
-    pub _mouse_look_sensitivity: f32,
-    pub _gamepad_look_sensitivity: f32,
-    pub _enable_view_bobbing: bool,
+    /// Rate at which the movement direction eases toward the input direction
+    /// (the `Motion::movement_vector` decay).
+    pub movement_decay: f32,
+    pub mouse_look_sensitivity: f32,
+    pub gamepad_look_sensitivity: f32,
+    /// Dip the ride height a little on each footstep.
+    pub enable_view_bobbing: bool,
+    /// Scales both the capsule and the ride height while crouched.
     pub crouched_height_factor: f32,
+    pub crouch_mode: CrouchMode,
+    /// Steepest ground (in degrees from level) the player can stand on. Anything
+    /// steeper is slid down.
+    pub max_walkable_slope_angle: f32,
+    /// Speed factor walking straight up the steepest walkable slope; shallower
+    /// slopes slow the player proportionally less.
+    pub uphill_speed_factor: f32,
+    /// Downhill acceleration on a vertical face; scaled by the sine of the slope.
+    pub slope_slide_acceleration: f32,
+    /// Fastest the player slides down steep ground, steering included.
+    pub max_slide_speed: f32,
+    /// Tallest ledge (above the feet) walked up without jumping.
+    pub max_step_height: f32,
+    /// Tallest ledge that can be mantled onto, roughly chest height.
+    pub max_mantle_height: f32,
+    /// Seconds a mantle takes from grabbing the ledge to standing on it.
+    pub mantle_duration: f32,
+    /// Seconds after walking off an edge during which a jump still works.
+    pub coyote_time: f32,
+    /// Seconds a jump press is remembered while the player cannot jump yet (e.g. just
+    /// before landing).
+    pub jump_buffer_time: f32,
+    /// Seconds after a jump during which holding jump keeps pushing up.
+    pub jump_hold_time: f32,
+    /// Extra upward acceleration while jump is held, easing to nothing over
+    /// `jump_hold_time`.
+    pub jump_hold_acceleration: f32,
+    /// Fraction of the rising speed kept when jump is let go early.
+    pub jump_release_velocity_factor: f32,
+    pub max_health: f32,
+    /// Landing slower than this (units/s toward the planet) does no damage.
+    pub fall_damage_min_speed: f32,
+    /// Damage per unit/s of landing speed beyond `fall_damage_min_speed`.
+    pub fall_damage_per_speed: f32,
+    /// Seconds between dying and respawning.
+    pub respawn_delay: f32,
+    pub max_stamina: f32,
+    /// Stamina per second while sprinting.
+    pub sprint_stamina_drain: f32,
+    pub jump_stamina_cost: f32,
+    /// Stamina per second regained once the regen delay has passed.
+    pub stamina_regen_rate: f32,
+    /// Seconds after the last drain before stamina regenerates.
+    pub stamina_regen_delay: f32,
+    /// Fraction of max stamina an exhausted player must regain before sprinting again.
+    pub stamina_recover_fraction: f32,
+    /// Fraction of max stamina below which footsteps and breathing show fatigue.
+    pub stamina_low_fraction: f32,
+    pub jetpack_max_fuel: f32,
+    /// Fuel per second at full thrust.
+    pub jetpack_fuel_burn: f32,
+    /// Fuel per second regained while on the ground.
+    pub jetpack_fuel_regen: f32,
+    /// Upward thrust; above the planet's gravity so the jetpack can climb.
+    pub jetpack_up_acceleration: f32,
+    /// Thrust along the camera's forward/right from the movement input.
+    pub jetpack_lateral_acceleration: f32,
+    /// Distance from the planet centre beyond which gravity stops and EVA begins.
+    pub eva_distance: f32,
+    pub eva_thrust_acceleration: f32,
+    /// Roll speed in EVA, radians per second.
+    pub eva_roll_speed: f32,
+    /// Speed along a climbable surface.
+    pub climb_speed: f32,
+    /// How far past the capsule's front a climbable surface can be grabbed.
+    pub climb_reach: f32,
+    /// Stamina per second while climbing.
+    pub climb_stamina_drain: f32,
+    /// Jumping off a climb: speed along `up`, and away from the surface.
+    pub climb_jump_speed: f32,
+    pub climb_push_off_speed: f32,
+    /// Scales the impulse the player gives dynamic bodies they walk into.
+    pub push_strength: f32,
+    /// Scales how hard dynamic bodies moving into the player shove them.
+    pub push_reaction: f32,
+    /// The heaviest body the player can pick up.
+    pub carry_max_mass: f32,
+    /// Closest to the camera a carried body is held.
+    pub carry_min_distance: f32,
+    /// Spring stiffness pulling a carried body to its hold point (damping is critical).
+    pub carry_stiffness: f32,
+    /// Cap on the carrying force, so heavier bodies respond more slowly.
+    pub carry_max_force: f32,
+    /// A carried body this far from its hold point is dropped.
+    pub carry_break_distance: f32,
+    /// Impulse of a throw, and the fastest a throw can launch a light body.
+    pub carry_throw_impulse: f32,
+    pub carry_throw_max_speed: f32,
+    /// How quickly a carried body turns to its held rotation.
+    pub carry_rotation_stiffness: f32,
+}
+
+/// How the crouch binding behaves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrouchMode {
+    /// Crouch while the binding is held.
+    Hold,
+    /// Each press flips between crouched and standing.
+    #[default]
+    Toggle,
 }
 
 impl Default for PlayerControlConfig {
@@ -42,11 +171,398 @@ impl Default for PlayerControlConfig {
             jump_strength: 250.0,
             default_movement_speed: 10.0,
             sprint_speed_factor: 2.0,
-            _movement_decay: 0.90,
-            _mouse_look_sensitivity: 0.0825,
-            _gamepad_look_sensitivity: 0.0012,
-            _enable_view_bobbing: true,
+            movement_decay: 16.0,
+            mouse_look_sensitivity: 0.0825,
+            gamepad_look_sensitivity: 0.0012,
+            enable_view_bobbing: true,
             crouched_height_factor: 0.80,
+            crouch_mode: CrouchMode::Toggle,
+            max_walkable_slope_angle: 45.0,
+            uphill_speed_factor: 0.6,
+            slope_slide_acceleration: 12.0,
+            max_slide_speed: 15.0,
+            max_step_height: 0.6,
+            max_mantle_height: 1.8,
+            mantle_duration: 0.4,
+            coyote_time: 0.12,
+            jump_buffer_time: 0.15,
+            jump_hold_time: 0.25,
+            jump_hold_acceleration: 20.0,
+            jump_release_velocity_factor: 0.5,
+            max_health: 100.0,
+            fall_damage_min_speed: 14.0,
+            fall_damage_per_speed: 8.0,
+            respawn_delay: 3.0,
+            max_stamina: 100.0,
+            sprint_stamina_drain: 15.0,
+            jump_stamina_cost: 10.0,
+            stamina_regen_rate: 20.0,
+            stamina_regen_delay: 1.0,
+            stamina_recover_fraction: 0.3,
+            stamina_low_fraction: 0.25,
+            jetpack_max_fuel: 100.0,
+            jetpack_fuel_burn: 20.0,
+            jetpack_fuel_regen: 10.0,
+            jetpack_up_acceleration: 16.0,
+            jetpack_lateral_acceleration: 8.0,
+            eva_distance: 700.0,
+            eva_thrust_acceleration: 6.0,
+            eva_roll_speed: 1.5,
+            climb_speed: 2.5,
+            climb_reach: 0.3,
+            climb_stamina_drain: 10.0,
+            climb_jump_speed: 5.0,
+            climb_push_off_speed: 3.0,
+            push_strength: 1.0,
+            push_reaction: 1.0,
+            carry_max_mass: 50.0,
+            carry_min_distance: 1.5,
+            carry_stiffness: 80.0,
+            carry_max_force: 800.0,
+            carry_break_distance: 2.0,
+            carry_throw_impulse: 60.0,
+            carry_throw_max_speed: 25.0,
+            carry_rotation_stiffness: 12.0,
         }
     }
-}
\ No newline at end of file
+}
+
+impl PlayerControlConfig {
+    /// Target ride-spring length for the given crouch state.
+    pub fn standing_ride_height(&self, crouched: bool) -> f32 {
+        if crouched {
+            self.ride_height * self.crouched_height_factor
+        } else {
+            self.ride_height
+        }
+    }
+}
+
+impl TomlFile for PlayerControlConfig {
+    /// Every out-of-range field, described by name. Empty when the config is usable.
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut check = |name: &str, value: f32, ok: bool, expected: &str| {
+            if !ok || !value.is_finite() {
+                problems.push(format!("`{name}` is {value}, expected {expected}"));
+            }
+        };
+        let positive = "a value greater than 0";
+        let non_negative = "a value of 0 or more";
+        check("capsule_height", self.capsule_height, self.capsule_height > 0.0, positive);
+        check("ride_height", self.ride_height, self.ride_height > 0.0, positive);
+        check(
+            "ride_height_step_offset",
+            self.ride_height_step_offset,
+            self.ride_height_step_offset >= 0.0,
+            non_negative,
+        );
+        check("ray_length_offset", self.ray_length_offset, self.ray_length_offset >= 0.0, non_negative);
+        check(
+            "stance_ground_hysteresis",
+            self.stance_ground_hysteresis,
+            self.stance_ground_hysteresis >= 0.0,
+            non_negative,
+        );
+        check("ride_spring_strength", self.ride_spring_strength, self.ride_spring_strength > 0.0, positive);
+        check("ride_spring_damper", self.ride_spring_damper, self.ride_spring_damper >= 0.0, non_negative);
+        check("stance_lockout", self.stance_lockout, self.stance_lockout >= 0.0, non_negative);
+        check("jump_strength", self.jump_strength, self.jump_strength >= 0.0, non_negative);
+        check(
+            "default_movement_speed",
+            self.default_movement_speed,
+            self.default_movement_speed > 0.0,
+            positive,
+        );
+        check(
+            "sprint_speed_factor",
+            self.sprint_speed_factor,
+            self.sprint_speed_factor >= 1.0,
+            "a value of 1 or more",
+        );
+        check("movement_decay", self.movement_decay, self.movement_decay > 0.0, positive);
+        check(
+            "mouse_look_sensitivity",
+            self.mouse_look_sensitivity,
+            self.mouse_look_sensitivity > 0.0,
+            positive,
+        );
+        check(
+            "gamepad_look_sensitivity",
+            self.gamepad_look_sensitivity,
+            self.gamepad_look_sensitivity > 0.0,
+            positive,
+        );
+        check(
+            "crouched_height_factor",
+            self.crouched_height_factor,
+            self.crouched_height_factor > 0.0 && self.crouched_height_factor <= 1.0,
+            "a value in (0, 1]",
+        );
+        check(
+            "max_walkable_slope_angle",
+            self.max_walkable_slope_angle,
+            self.max_walkable_slope_angle > 0.0 && self.max_walkable_slope_angle < 90.0,
+            "an angle in (0, 90)",
+        );
+        check(
+            "uphill_speed_factor",
+            self.uphill_speed_factor,
+            self.uphill_speed_factor > 0.0 && self.uphill_speed_factor <= 1.0,
+            "a value in (0, 1]",
+        );
+        check(
+            "slope_slide_acceleration",
+            self.slope_slide_acceleration,
+            self.slope_slide_acceleration >= 0.0,
+            non_negative,
+        );
+        check(
+            "max_slide_speed",
+            self.max_slide_speed,
+            self.max_slide_speed > 0.0,
+            positive,
+        );
+        check(
+            "max_step_height",
+            self.max_step_height,
+            self.max_step_height >= 0.0,
+            non_negative,
+        );
+        check(
+            "max_mantle_height",
+            self.max_mantle_height,
+            self.max_mantle_height >= self.max_step_height,
+            "a value of at least `max_step_height`",
+        );
+        check("mantle_duration", self.mantle_duration, self.mantle_duration > 0.0, positive);
+        check("coyote_time", self.coyote_time, self.coyote_time >= 0.0, non_negative);
+        check(
+            "jump_buffer_time",
+            self.jump_buffer_time,
+            self.jump_buffer_time >= 0.0,
+            non_negative,
+        );
+        check("jump_hold_time", self.jump_hold_time, self.jump_hold_time > 0.0, positive);
+        check(
+            "jump_hold_acceleration",
+            self.jump_hold_acceleration,
+            self.jump_hold_acceleration >= 0.0,
+            non_negative,
+        );
+        check(
+            "jump_release_velocity_factor",
+            self.jump_release_velocity_factor,
+            (0.0..=1.0).contains(&self.jump_release_velocity_factor),
+            "a value in [0, 1]",
+        );
+        check("max_health", self.max_health, self.max_health > 0.0, positive);
+        check(
+            "fall_damage_min_speed",
+            self.fall_damage_min_speed,
+            self.fall_damage_min_speed >= 0.0,
+            non_negative,
+        );
+        check(
+            "fall_damage_per_speed",
+            self.fall_damage_per_speed,
+            self.fall_damage_per_speed >= 0.0,
+            non_negative,
+        );
+        check("respawn_delay", self.respawn_delay, self.respawn_delay >= 0.0, non_negative);
+        check("max_stamina", self.max_stamina, self.max_stamina > 0.0, positive);
+        check(
+            "sprint_stamina_drain",
+            self.sprint_stamina_drain,
+            self.sprint_stamina_drain >= 0.0,
+            non_negative,
+        );
+        check(
+            "jump_stamina_cost",
+            self.jump_stamina_cost,
+            self.jump_stamina_cost >= 0.0,
+            non_negative,
+        );
+        check(
+            "stamina_regen_rate",
+            self.stamina_regen_rate,
+            self.stamina_regen_rate >= 0.0,
+            non_negative,
+        );
+        check(
+            "stamina_regen_delay",
+            self.stamina_regen_delay,
+            self.stamina_regen_delay >= 0.0,
+            non_negative,
+        );
+        check(
+            "stamina_recover_fraction",
+            self.stamina_recover_fraction,
+            (0.0..=1.0).contains(&self.stamina_recover_fraction),
+            "a value in [0, 1]",
+        );
+        check(
+            "stamina_low_fraction",
+            self.stamina_low_fraction,
+            (0.0..=1.0).contains(&self.stamina_low_fraction),
+            "a value in [0, 1]",
+        );
+        check("jetpack_max_fuel", self.jetpack_max_fuel, self.jetpack_max_fuel > 0.0, positive);
+        check(
+            "jetpack_fuel_burn",
+            self.jetpack_fuel_burn,
+            self.jetpack_fuel_burn >= 0.0,
+            non_negative,
+        );
+        check(
+            "jetpack_fuel_regen",
+            self.jetpack_fuel_regen,
+            self.jetpack_fuel_regen >= 0.0,
+            non_negative,
+        );
+        check(
+            "jetpack_up_acceleration",
+            self.jetpack_up_acceleration,
+            self.jetpack_up_acceleration >= 0.0,
+            non_negative,
+        );
+        check(
+            "jetpack_lateral_acceleration",
+            self.jetpack_lateral_acceleration,
+            self.jetpack_lateral_acceleration >= 0.0,
+            non_negative,
+        );
+        check("eva_distance", self.eva_distance, self.eva_distance > 0.0, positive);
+        check(
+            "eva_thrust_acceleration",
+            self.eva_thrust_acceleration,
+            self.eva_thrust_acceleration > 0.0,
+            positive,
+        );
+        check("eva_roll_speed", self.eva_roll_speed, self.eva_roll_speed >= 0.0, non_negative);
+        check("climb_speed", self.climb_speed, self.climb_speed > 0.0, positive);
+        check("climb_reach", self.climb_reach, self.climb_reach > 0.0, positive);
+        check(
+            "climb_stamina_drain",
+            self.climb_stamina_drain,
+            self.climb_stamina_drain >= 0.0,
+            non_negative,
+        );
+        check(
+            "climb_jump_speed",
+            self.climb_jump_speed,
+            self.climb_jump_speed >= 0.0,
+            non_negative,
+        );
+        check(
+            "climb_push_off_speed",
+            self.climb_push_off_speed,
+            self.climb_push_off_speed >= 0.0,
+            non_negative,
+        );
+        check("push_strength", self.push_strength, self.push_strength >= 0.0, non_negative);
+        check("push_reaction", self.push_reaction, self.push_reaction >= 0.0, non_negative);
+        check("carry_max_mass", self.carry_max_mass, self.carry_max_mass >= 0.0, non_negative);
+        check(
+            "carry_min_distance",
+            self.carry_min_distance,
+            self.carry_min_distance > 0.0,
+            positive,
+        );
+        check("carry_stiffness", self.carry_stiffness, self.carry_stiffness > 0.0, positive);
+        check("carry_max_force", self.carry_max_force, self.carry_max_force > 0.0, positive);
+        check(
+            "carry_break_distance",
+            self.carry_break_distance,
+            self.carry_break_distance > 0.0,
+            positive,
+        );
+        check(
+            "carry_throw_impulse",
+            self.carry_throw_impulse,
+            self.carry_throw_impulse >= 0.0,
+            non_negative,
+        );
+        check(
+            "carry_throw_max_speed",
+            self.carry_throw_max_speed,
+            self.carry_throw_max_speed >= 0.0,
+            non_negative,
+        );
+        check(
+            "carry_rotation_stiffness",
+            self.carry_rotation_stiffness,
+            self.carry_rotation_stiffness >= 0.0,
+            non_negative,
+        );
+        problems
+    }
+}
+
+/// Keeps the loaded config asset alive (and watched for changes).
+#[derive(Resource)]
+pub struct PlayerConfigHandle(pub Handle<PlayerControlConfig>);
+
+pub fn load_player_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PlayerConfigHandle(asset_server.load(PLAYER_CONFIG_PATH)));
+}
+
+/// Copy the config asset into the [`PlayerControlConfig`] resource whenever it
+/// (re)loads, then push the values that are cached elsewhere out to their owners. A
+/// file that fails to load or validate is reported and the current config is kept.
+pub fn apply_player_config(
+    handle: Option<Res<PlayerConfigHandle>>,
+    assets: Res<Assets<PlayerControlConfig>>,
+    mut asset_events: MessageReader<AssetEvent<PlayerControlConfig>>,
+    mut failed_events: MessageReader<AssetLoadFailedEvent<PlayerControlConfig>>,
+    mut config: ResMut<PlayerControlConfig>,
+    mut input_config: ResMut<InputConfig>,
+    mut player_query: Query<
+        (
+            &mut Motion,
+            &mut StandingSpringForce,
+            &mut Health,
+            &mut Stamina,
+            &mut Jetpack,
+            &Stance,
+        ),
+        With<Player>,
+    >,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for failed in failed_events.read() {
+        if failed.id == handle.0.id() {
+            error!("{}: {}; keeping the current player config", failed.path, failed.error);
+        }
+    }
+    let mut reloaded = false;
+    for event in asset_events.read() {
+        if (event.is_added(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = assets.get(&handle.0)
+        {
+            *config = loaded.clone();
+            reloaded = true;
+        }
+    }
+    if !reloaded {
+        return;
+    }
+    info!("Applied player config from {PLAYER_CONFIG_PATH}");
+
+    input_config.mouse_look_sensitivity = config.mouse_look_sensitivity;
+    input_config.gamepad_look_sensitivity = config.gamepad_look_sensitivity;
+    for (mut motion, mut standing_spring_force, mut health, mut stamina, mut jetpack, stance) in
+        &mut player_query
+    {
+        motion.movement_vector.decay = config.movement_decay;
+        standing_spring_force.length.target = config.standing_ride_height(stance.crouched);
+        health.max = config.max_health;
+        health.current = health.current.min(health.max);
+        stamina.max = config.max_stamina;
+        stamina.current = stamina.current.min(stamina.max);
+        jetpack.max_fuel = config.jetpack_max_fuel;
+        jetpack.fuel = jetpack.fuel.min(jetpack.max_fuel);
+    }
+}
@@ -8,7 +8,10 @@ use bevy::{
         system::{Commands, Query, Res},
     }, input::{ButtonInput, keyboard::KeyCode}, math::{EulerRot, Quat}, time::Time, transform::components::Transform
 };
-use kosim_camera::GameCamera;
+use kosim_camera::{
+    GameCamera,
+    third_person_camera::{ThirdPersonCamera, camera_reach_offset},
+};
 use kosim_input::{binding::Bindings, input::Input};
 use kosim_utility::exp_decay;
 
@@ -57,13 +60,13 @@ pub fn camera_look_system(
 
 
 pub fn update_focus_target(
-    focus: Query<(Entity, &RayHits), With<GameCamera>>,
+    focus: Query<(Entity, &RayHits, Option<&ThirdPersonCamera>), With<GameCamera>>,
     previous_focus: Query<Entity, (With<FocusTarget>, Without<GameCamera>)>,
     ignored_entities: Query<Entity, With<IgnoreRayCollision>>,
     mut commands: Commands,
 ) {
     // Compute the ray_length to a hit, if we don't hit anything we assume the ground is infinitly far away.
-    let (entity, ray_hits) = focus.single().unwrap();
+    let (entity, ray_hits, third_person) = focus.single().unwrap();
     let mut ray_length: f32 = f32::INFINITY;
     let mut hit_entity: Option<Entity> = None;
 
@@ -89,7 +92,8 @@ pub fn update_focus_target(
 
     let previous_focus_entity: Option<Entity> = previous_focus.iter().next();
 
-    if ray_length.is_infinite() || ray_length > FOCUS_DISTANCE {
+    let reach = FOCUS_DISTANCE + camera_reach_offset(third_person);
+    if ray_length.is_infinite() || ray_length > reach {
         // Distance is infinite, remove FocusTarget from previous_focus entity
         if let Some(prev_entity) = previous_focus_entity {
             commands.entity(prev_entity).remove::<FocusTarget>();
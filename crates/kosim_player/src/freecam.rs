@@ -1,124 +1,243 @@
 //! Detached free-fly camera for inspecting the world.
 //!
-//! Pressing [`Bindings::action_toggle_camera_mode`] (F3) toggles free-cam mode. On
-//! entering, the [`GameCamera`] is un-parented from the player and keeps its current
-//! world pose; [`free_cam_control`] then flies it directly with the usual
-//! move/look input (WASD + mouse, Space/Shift to rise/fall, Ctrl to boost). On
-//! exiting, the camera is re-parented to the player and the normal first-person
-//! systems resume.
+//! [`kosim_camera::mode`] owns the camera mode: cycling to [`CameraMode::FreeCam`]
+//! un-parents the [`GameCamera`] from the player, keeping its current world pose.
+//! [`seed_free_cam`] then takes the look angles from that pose and
+//! [`free_cam_control`] flies the camera directly with the usual move/look input
+//! (WASD + mouse, Space/Shift to rise/fall, Ctrl to boost).
+//!
+//! By default the free cam is planet-relative: Space/Shift rise and fall along the
+//! local radial up and look stays level with the local horizon, wherever on the
+//! planet the camera is. [`Bindings::action_toggle_free_cam_planet_relative`] (P)
+//! switches to plain world axes. Speed grows with altitude above the surface, so
+//! crossing the planet from orbit takes as long as crossing a valley on foot, and
+//! the scroll wheel scales it further. [`Bindings::action_free_cam_teleport`] (Y)
+//! leaves free cam with the player moved to the camera.
 //!
 //! While free-cam is active the player-control systems ([`camera_look_system`],
-//! rotation, motion, move-and-slide) are gated off via [`player_control_active`] so
-//! the shared input does not drive the player at the same time — the player simply
-//! parks in place.
+//! rotation, motion, move-and-slide, jumping, crouching and the rest) are gated off
+//! via [`player_control_active`] so the shared input does not drive the player at
+//! the same time — the player simply parks in place.
+//!
+//! [`camera_look_system`]: crate::focus::camera_look_system
+//! [`player_control_active`]: kosim_camera::mode::player_control_active
 
-use bevy::ecs::hierarchy::ChildOf;
-use bevy::ecs::query::With;
+use avian3d::prelude::LinearVelocity;
+use bevy::ecs::change_detection::DetectChanges;
+use bevy::ecs::message::{MessageReader, MessageWriter};
+use bevy::ecs::query::{With, Without};
 use bevy::input::ButtonInput;
 use bevy::input::keyboard::KeyCode;
-use bevy::log::info;
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::log::{info, warn};
 use bevy::math::{EulerRot, Quat, Vec3};
-use bevy::prelude::{Commands, Entity, GlobalTransform, Query, Res, ResMut, Resource};
+use bevy::prelude::{Query, Res, ResMut, Resource};
 use bevy::time::Time;
 use bevy::transform::components::Transform;
-use kosim_camera::GameCamera;
+use kosim_camera::mode::{FIRST_PERSON_OFFSET, SetCameraMode};
+use kosim_camera::path::CameraPathPlayback;
+use kosim_camera::{CameraMode, GameCamera, ToggleCameraEvent};
 use kosim_input::binding::Bindings;
 use kosim_input::input::Input;
+use kosim_world::ChunkManager;
 
 use crate::Player;
+use crate::gravity::{PlanetGravity, up_at};
+use crate::health::Dead;
+use crate::motion::Motion;
+use crate::stance::{Stance, StanceType};
 
 /// Base fly speed in units/second, and the multiplier applied while boosting.
 const FREE_CAM_SPEED: f32 = 25.0;
 const FREE_CAM_BOOST: f32 = 4.0;
+/// Altitude above the surface that adds another [`FREE_CAM_SPEED`].
+const FREE_CAM_SPEED_ALTITUDE: f32 = 50.0;
+/// Speed multiplier per scroll-wheel notch, and the range the scroll can reach.
+const FREE_CAM_SCROLL_STEP: f32 = 1.1;
+const FREE_CAM_SPEED_SCALE_MIN: f32 = 0.05;
+const FREE_CAM_SPEED_SCALE_MAX: f32 = 20.0;
+/// Pixels of touchpad scroll that count as one wheel notch.
+const SCROLL_PIXELS_PER_NOTCH: f32 = 100.0;
 
-/// State for the detached free-fly camera. `yaw`/`pitch` are tracked here rather
-/// than read back from the transform so look stays stable and gimbal-free.
-#[derive(Resource, Default)]
+/// State of the detached free-fly camera. `yaw`/`pitch` are tracked here rather
+/// than read back from the transform so look stays stable and gimbal-free; in
+/// planet-relative mode only `pitch` is kept (above the local horizon), and the
+/// heading is carried along the horizon by [`free_cam_control`].
+#[derive(Resource)]
 pub struct FreeCam {
-    pub active: bool,
     pub yaw: f32,
     pub pitch: f32,
+    /// Rise/fall along the local radial up and keep look level with the horizon.
+    pub planet_relative: bool,
+    /// Scroll-wheel multiplier on the fly speed.
+    pub speed_scale: f32,
+    /// The teleport key was pressed; latched in `Update` by [`adjust_free_cam`] for
+    /// [`teleport_player_to_free_cam`] to take on the next fixed tick.
+    teleport_requested: bool,
 }
 
-/// Run condition: player-control systems run only while the free cam is *off*.
-pub fn player_control_active(free: Res<FreeCam>) -> bool {
-    !free.active
+impl Default for FreeCam {
+    fn default() -> Self {
+        Self {
+            yaw: 0.0,
+            pitch: 0.0,
+            planet_relative: true,
+            speed_scale: 1.0,
+            teleport_requested: false,
+        }
+    }
 }
 
-/// Run condition: the free-cam controller runs only while it is *on*.
-pub fn free_cam_active(free: Res<FreeCam>) -> bool {
-    free.active
+impl FreeCam {
+    /// Take the look angles from `rotation`, in whichever frame is in use.
+    fn seed(&mut self, rotation: Quat, up: Vec3) {
+        if self.planet_relative {
+            let forward = rotation * Vec3::NEG_Z;
+            self.pitch = forward.dot(up).clamp(-1.0, 1.0).asin();
+        } else {
+            let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+            self.yaw = yaw;
+            self.pitch = pitch;
+        }
+        self.pitch = self.pitch.clamp(-1.54, 1.54);
+    }
 }
 
-/// Toggle free-cam mode on the toggle key: detach/reattach the camera and seed the
-/// look angles so there is no jump.
-pub fn toggle_free_cam(
-    keys: Res<ButtonInput<KeyCode>>,
-    bindings: Res<Bindings>,
+/// The horizontal direction `rotation` faces in on the horizon of `up`.
+fn horizon_heading(rotation: Quat, up: Vec3) -> Vec3 {
+    (rotation * Vec3::NEG_Z)
+        .reject_from_normalized(up)
+        .try_normalize()
+        // Looking straight down (or up): the top of the view points ahead.
+        .or_else(|| {
+            (rotation * Vec3::Y)
+                .reject_from_normalized(up)
+                .try_normalize()
+        })
+        .unwrap_or_else(|| up.any_orthonormal_vector())
+}
+
+/// On entering free cam, seed the look angles from the pose the camera was left at
+/// so there is no jump.
+pub fn seed_free_cam(
+    mut ev_toggle_cam: MessageReader<ToggleCameraEvent>,
     mut free: ResMut<FreeCam>,
-    mut commands: Commands,
-    player_query: Query<Entity, With<Player>>,
-    mut camera_query: Query<(Entity, &mut Transform, &GlobalTransform), With<GameCamera>>,
+    gravity: Res<PlanetGravity>,
+    camera_query: Query<&Transform, With<GameCamera>>,
 ) {
-    if !keys.just_pressed(bindings.action_toggle_camera_mode) {
+    if !ev_toggle_cam
+        .read()
+        .any(|event| event.mode == CameraMode::FreeCam)
+    {
         return;
     }
-    let Ok((camera, mut transform, global)) = camera_query.single_mut() else {
+    free.teleport_requested = false;
+    let Ok(transform) = camera_query.single() else {
         return;
     };
+    free.seed(
+        transform.rotation,
+        up_at(transform.translation, gravity.center),
+    );
+}
 
-    if !free.active {
-        // Enter: detach and keep the exact world pose the camera had as a child.
-        let world = global.compute_transform();
-        *transform = world;
-        let (yaw, pitch, _) = world.rotation.to_euler(EulerRot::YXZ);
-        free.yaw = yaw;
-        free.pitch = pitch;
-        commands.entity(camera).remove::<ChildOf>();
-        free.active = true;
-        info!("Free cam ON (F3 to exit)");
-    } else {
-        // Exit: re-parent to the player and reset to the first-person offset.
-        if let Ok(player) = player_query.single() {
-            commands.entity(player).add_children(&[camera]);
-            transform.translation = Vec3::new(0.0, 1.0, 0.0);
-            transform.rotation = Quat::IDENTITY;
-        }
-        free.active = false;
-        info!("Free cam OFF");
+/// Scale the fly speed with the scroll wheel, switch between planet-relative and
+/// world axes, and latch the teleport key. Runs in `Update`, where the scroll is
+/// accumulated per frame and key presses last exactly one frame.
+pub fn adjust_free_cam(
+    mut free: ResMut<FreeCam>,
+    scroll: Res<AccumulatedMouseScroll>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    gravity: Res<PlanetGravity>,
+    camera_query: Query<&Transform, With<GameCamera>>,
+) {
+    let notches = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / SCROLL_PIXELS_PER_NOTCH,
+    };
+    if notches != 0.0 {
+        free.speed_scale = (free.speed_scale * FREE_CAM_SCROLL_STEP.powf(notches))
+            .clamp(FREE_CAM_SPEED_SCALE_MIN, FREE_CAM_SPEED_SCALE_MAX);
+    }
+
+    if keys.just_pressed(bindings.action_toggle_free_cam_planet_relative)
+        && let Ok(transform) = camera_query.single()
+    {
+        free.planet_relative = !free.planet_relative;
+        free.seed(
+            transform.rotation,
+            up_at(transform.translation, gravity.center),
+        );
+        info!(
+            "Free cam: {} axes",
+            if free.planet_relative {
+                "planet-relative"
+            } else {
+                "world"
+            }
+        );
+    }
+
+    if keys.just_pressed(bindings.action_free_cam_teleport) {
+        free.teleport_requested = true;
     }
 }
 
 /// Fly the detached camera: mouse look (free yaw + clamped pitch) and WASD/Space/
 /// Shift movement in the camera's own basis, with Ctrl to boost. Runs in
-/// `FixedUpdate` so it reads the input resource before it is cleared.
+/// `FixedUpdate` so it reads the input resource before it is cleared. Stands aside
+/// while a camera path plays, and takes over from wherever it left the camera.
 pub fn free_cam_control(
     mut free: ResMut<FreeCam>,
     input: Res<Input>,
     keys: Res<ButtonInput<KeyCode>>,
     bindings: Res<Bindings>,
+    gravity: Res<PlanetGravity>,
+    chunks: Option<Res<ChunkManager>>,
+    playback: Res<CameraPathPlayback>,
     time: Res<Time>,
     mut camera_query: Query<&mut Transform, With<GameCamera>>,
 ) {
     let Ok(mut transform) = camera_query.single_mut() else {
         return;
     };
+    if playback.is_changed() {
+        free.seed(
+            transform.rotation,
+            up_at(transform.translation, gravity.center),
+        );
+    }
 
     // Look: accumulate raw mouse delta into stored yaw/pitch.
-    free.yaw -= input.focus_delta_raw.x.to_radians();
+    let yaw_delta = -input.focus_delta_raw.x.to_radians();
     free.pitch -= input.focus_delta_raw.y.to_radians();
     free.pitch = free.pitch.clamp(-1.54, 1.54);
-    let rotation = Quat::from_euler(EulerRot::YXZ, free.yaw, free.pitch, 0.0);
+    let (rotation, up) = if free.planet_relative {
+        // Carry the heading along the horizon as the camera moves over the sphere,
+        // turning it about the local up.
+        let up = up_at(transform.translation, gravity.center);
+        let heading =
+            Quat::from_axis_angle(up, yaw_delta) * horizon_heading(transform.rotation, up);
+        let level = Transform::IDENTITY.looking_to(heading, up).rotation;
+        (level * Quat::from_rotation_x(free.pitch), up)
+    } else {
+        free.yaw += yaw_delta;
+        (
+            Quat::from_euler(EulerRot::YXZ, free.yaw, free.pitch, 0.0),
+            Vec3::Y,
+        )
+    };
     transform.rotation = rotation;
 
     // Move in the camera's basis. `movement_raw.z` is forward (+W), `.x` strafe (+D).
-    let mut direction = rotation * Vec3::NEG_Z * input.movement_raw.z
-        + rotation * Vec3::X * input.movement_raw.x;
+    let mut direction =
+        rotation * Vec3::NEG_Z * input.movement_raw.z + rotation * Vec3::X * input.movement_raw.x;
     if keys.pressed(bindings.move_ascend) {
-        direction += Vec3::Y;
+        direction += up;
     }
     if keys.pressed(bindings.move_descend) {
-        direction -= Vec3::Y;
+        direction -= up;
     }
     if direction != Vec3::ZERO {
         let boost = if keys.pressed(KeyCode::ControlLeft) {
@@ -126,6 +245,56 @@ pub fn free_cam_control(
         } else {
             1.0
         };
-        transform.translation += direction.normalize() * FREE_CAM_SPEED * boost * time.delta_secs();
+        let altitude = chunks.map_or(0.0, |chunks| {
+            chunks.world().altitude(transform.translation).max(0.0)
+        });
+        let speed =
+            FREE_CAM_SPEED * (1.0 + altitude / FREE_CAM_SPEED_ALTITUDE) * free.speed_scale * boost;
+        transform.translation += direction.normalize() * speed * time.delta_secs();
+    }
+}
+
+/// On a latched teleport press, move the player so their eyes are at the free cam,
+/// upright and facing its heading, and return to first person.
+pub fn teleport_player_to_free_cam(
+    mut free: ResMut<FreeCam>,
+    gravity: Res<PlanetGravity>,
+    chunks: Option<Res<ChunkManager>>,
+    camera_query: Query<&Transform, (With<GameCamera>, Without<Player>)>,
+    mut player_query: Query<
+        (
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut Motion,
+            &mut Stance,
+        ),
+        (With<Player>, Without<Dead>),
+    >,
+    mut ev_set_mode: MessageWriter<SetCameraMode>,
+) {
+    if !std::mem::take(&mut free.teleport_requested) {
+        return;
     }
+    let (Ok(camera), Ok((mut transform, mut linear_velocity, mut motion, mut stance))) =
+        (camera_query.single(), player_query.single_mut())
+    else {
+        return;
+    };
+    let up = up_at(camera.translation, gravity.center);
+    let target = camera.translation - up * FIRST_PERSON_OFFSET.y;
+    if chunks.is_some_and(|chunks| chunks.world().altitude(target) < 0.0) {
+        warn!("Free cam is underground, not teleporting the player there");
+        return;
+    }
+
+    transform.translation = target;
+    transform.rotation = Transform::IDENTITY
+        .looking_to(horizon_heading(camera.rotation, up), up)
+        .rotation;
+    linear_velocity.0 = Vec3::ZERO;
+    motion.linear_velocity_interp.current = Vec3::ZERO;
+    motion.linear_velocity_interp.target = Vec3::ZERO;
+    stance.current = StanceType::Airborne;
+    info!("Teleported the player to {}", target);
+    ev_set_mode.write(SetCameraMode(CameraMode::FirstPerson));
 }
@@ -0,0 +1,279 @@
+//! Player health, damage and the death/respawn flow.
+//!
+//! Anything that hurts or heals writes a [`DamageEvent`] or [`HealEvent`];
+//! [`apply_health_events`] is the only system that changes [`Health`]. Fall damage
+//! is one such source (written by `compute_next_stance` on touchdown); hazards and
+//! combat plug in the same way with their own [`DamageSource`].
+//!
+//! At zero health the entity gets [`Dead`], player control stops, and after
+//! [`PlayerControlConfig::respawn_delay`] it is put back on the planet surface at
+//! the [`PlayerSpawnPoint`] with full health. Every change is announced as a
+//! [`HealthChanged`] message for the HUD.
+
+use avian3d::prelude::LinearVelocity;
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::Event,
+        message::{Message, MessageReader, MessageWriter},
+        query::{Has, With},
+        resource::Resource,
+        system::{Commands, Query, Res},
+    },
+    log::info,
+    math::Vec3,
+    time::Time,
+    transform::components::Transform,
+};
+use kosim_world::ChunkManager;
+
+use crate::{
+    Player,
+    config::PlayerControlConfig,
+    gravity::PlanetGravity,
+    locomotion::Locomotion,
+    motion::Motion,
+    stance::{Stance, StanceType},
+};
+
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    /// Health as a fraction of the maximum, in `[0, 1]`.
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// What dealt a [`DamageEvent`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DamageSource {
+    /// Hit the ground too fast; `impact_speed` is the speed toward the planet.
+    Fall { impact_speed: f32 },
+    /// An environmental hazard entity (lava, spikes, ...).
+    Hazard(Entity),
+    /// Another entity's attack.
+    Attack(Entity),
+    /// Anything else, described for logs and death messages.
+    Other(&'static str),
+}
+
+#[derive(Message, Event, Clone)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub source: DamageSource,
+}
+
+#[derive(Message, Event, Clone)]
+pub struct HealEvent {
+    pub target: Entity,
+    pub amount: f32,
+}
+
+/// Sent whenever an entity's [`Health`] changes, including on respawn.
+#[derive(Message, Event, Clone)]
+pub struct HealthChanged {
+    pub entity: Entity,
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(Message, Event, Clone)]
+pub struct Died {
+    pub entity: Entity,
+    pub source: DamageSource,
+}
+
+#[derive(Message, Event, Clone)]
+pub struct Respawned {
+    pub entity: Entity,
+}
+
+/// Marks a dead entity until it respawns.
+#[derive(Component)]
+pub struct Dead {
+    pub respawn_timer: f32,
+    pub source: DamageSource,
+}
+
+/// Where the player respawns: the planet surface along `direction` from the planet
+/// centre. `fallback_height` (from the centre) is used when the surface cannot be
+/// found, e.g. before any terrain is loaded.
+#[derive(Resource)]
+pub struct PlayerSpawnPoint {
+    pub direction: Vec3,
+    pub fallback_height: f32,
+}
+
+impl Default for PlayerSpawnPoint {
+    fn default() -> Self {
+        Self {
+            // The north pole, where `spawn_player` starts the player.
+            direction: Vec3::Y,
+            fallback_height: 478.0,
+        }
+    }
+}
+
+/// How far out from the planet centre the respawn probe starts looking for ground.
+const SPAWN_PROBE_START: f32 = 512.0;
+
+/// Fall damage for touching down at `impact_speed` toward the planet: nothing up to
+/// `fall_damage_min_speed`, then `fall_damage_per_speed` per unit/s beyond it.
+pub fn fall_damage(impact_speed: f32, config: &PlayerControlConfig) -> f32 {
+    (impact_speed - config.fall_damage_min_speed).max(0.0) * config.fall_damage_per_speed
+}
+
+/// Apply this frame's damage and healing, announce the changes, and kill anything
+/// that reaches zero.
+pub fn apply_health_events(
+    mut query: Query<(&mut Health, Has<Dead>)>,
+    mut ev_damage: MessageReader<DamageEvent>,
+    mut ev_heal: MessageReader<HealEvent>,
+    mut ev_changed: MessageWriter<HealthChanged>,
+    mut ev_died: MessageWriter<Died>,
+    config: Res<PlayerControlConfig>,
+    mut commands: Commands,
+) {
+    for heal in ev_heal.read() {
+        let Ok((mut health, dead)) = query.get_mut(heal.target) else {
+            continue;
+        };
+        if dead || heal.amount <= 0.0 {
+            continue;
+        }
+        health.current = (health.current + heal.amount).min(health.max);
+        ev_changed.write(HealthChanged {
+            entity: heal.target,
+            current: health.current,
+            max: health.max,
+        });
+    }
+
+    for damage in ev_damage.read() {
+        let Ok((mut health, dead)) = query.get_mut(damage.target) else {
+            continue;
+        };
+        if dead || health.is_dead() || damage.amount <= 0.0 {
+            continue;
+        }
+        health.current = (health.current - damage.amount).max(0.0);
+        info!(
+            "Took {:.1} damage from {:?}, health {:.1}/{:.1}",
+            damage.amount, damage.source, health.current, health.max
+        );
+        ev_changed.write(HealthChanged {
+            entity: damage.target,
+            current: health.current,
+            max: health.max,
+        });
+        if health.is_dead() {
+            info!("Died from {:?}", damage.source);
+            ev_died.write(Died {
+                entity: damage.target,
+                source: damage.source.clone(),
+            });
+            commands.entity(damage.target).insert(Dead {
+                respawn_timer: config.respawn_delay,
+                source: damage.source.clone(),
+            });
+        }
+    }
+}
+
+/// Run condition: player-control systems run only while the player is alive.
+pub fn player_alive(query: Query<(), (With<Player>, With<Dead>)>) -> bool {
+    query.is_empty()
+}
+
+/// Hold dead players in place until their respawn timer runs out, then put them
+/// back on the surface at the spawn point with full health.
+pub fn respawn_dead_players(
+    mut query: Query<
+        (
+            Entity,
+            &mut Dead,
+            &mut Health,
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut Motion,
+            &mut Stance,
+            &mut Locomotion,
+        ),
+        With<Player>,
+    >,
+    spawn_point: Res<PlayerSpawnPoint>,
+    gravity: Res<PlanetGravity>,
+    chunks: Option<Res<ChunkManager>>,
+    config: Res<PlayerControlConfig>,
+    mut ev_changed: MessageWriter<HealthChanged>,
+    mut ev_respawned: MessageWriter<Respawned>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (
+        entity,
+        mut dead,
+        mut health,
+        mut transform,
+        mut linear_velocity,
+        mut motion,
+        mut stance,
+        mut locomotion,
+    ) in &mut query
+    {
+        linear_velocity.0 = Vec3::ZERO;
+        motion.linear_velocity_interp.current = Vec3::ZERO;
+        motion.linear_velocity_interp.target = Vec3::ZERO;
+        dead.respawn_timer -= time.delta_secs();
+        if dead.respawn_timer > 0.0 {
+            continue;
+        }
+
+        let up = spawn_point.direction.normalize_or(Vec3::Y);
+        let surface = chunks.as_ref().and_then(|chunks| {
+            chunks
+                .world()
+                .raycast(
+                    gravity.center + up * SPAWN_PROBE_START,
+                    -up,
+                    SPAWN_PROBE_START,
+                )
+                .map(|hit| hit.point)
+        });
+        // Drop in from just above the ride height so the spring catches the body.
+        transform.translation = match surface {
+            Some(point) => point + up * (config.ride_height + config.capsule_height),
+            None => gravity.center + up * spawn_point.fallback_height,
+        };
+        health.current = health.max;
+        stance.current = StanceType::Airborne;
+        *locomotion = Locomotion::default();
+        commands.entity(entity).remove::<Dead>();
+        info!("Respawned at {}", transform.translation);
+        ev_changed.write(HealthChanged {
+            entity,
+            current: health.current,
+            max: health.max,
+        });
+        ev_respawned.write(Respawned { entity });
+    }
+}
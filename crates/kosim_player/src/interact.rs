@@ -0,0 +1,155 @@
+//! Interacting with the focused object.
+//!
+//! An entity with an [`Interactable`] that the camera is focused on (it carries
+//! [`FocusTarget`]) can be interacted with through the bound interact action. Most
+//! verbs fire on press; an interactable with a [`Interactable::hold_time`] has to be
+//! held that long, with progress tracked in [`InteractProgress`] for the HUD. Either
+//! way the result is an [`InteractEvent`] for whatever owns the verb.
+
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::Event,
+        message::{Message, MessageWriter},
+        query::With,
+        system::{Query, Res},
+    },
+    input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
+    log::info,
+    time::Time,
+};
+use kosim_input::binding::Bindings;
+
+use crate::{Player, focus::FocusTarget};
+
+/// What interacting with an [`Interactable`] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractVerb {
+    Use,
+    PickUp,
+    /// Put it in the inventory.
+    Take,
+    Open,
+    Talk,
+}
+
+impl InteractVerb {
+    /// The verb as shown in prompts.
+    pub fn label(self) -> &'static str {
+        match self {
+            InteractVerb::Use => "Use",
+            InteractVerb::PickUp => "Pick up",
+            InteractVerb::Take => "Take",
+            InteractVerb::Open => "Open",
+            InteractVerb::Talk => "Talk",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Interactable {
+    pub verb: InteractVerb,
+    /// Seconds the interact action must be held; zero fires on press.
+    pub hold_time: f32,
+    pub enabled: bool,
+}
+
+impl Interactable {
+    pub fn new(verb: InteractVerb) -> Self {
+        Self {
+            verb,
+            hold_time: 0.0,
+            enabled: true,
+        }
+    }
+
+    pub fn with_hold_time(mut self, hold_time: f32) -> Self {
+        self.hold_time = hold_time;
+        self
+    }
+}
+
+/// Sent when the player interacts with an [`Interactable`].
+#[derive(Message, Event, Clone)]
+pub struct InteractEvent {
+    pub player: Entity,
+    pub target: Entity,
+    pub verb: InteractVerb,
+}
+
+/// The player's interaction in progress with the focused object.
+#[derive(Component, Default)]
+pub struct InteractProgress {
+    /// The focused interactable, if any.
+    pub target: Option<Entity>,
+    /// Seconds the interact action has been held on `target`.
+    pub elapsed: f32,
+    /// This hold already fired; the action must be released before it fires again.
+    pub fired: bool,
+}
+
+impl InteractProgress {
+    /// How far through a hold-to-interact the player is, in `[0, 1]`.
+    pub fn fraction(&self, interactable: &Interactable) -> f32 {
+        if interactable.hold_time <= 0.0 {
+            0.0
+        } else {
+            (self.elapsed / interactable.hold_time).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Track the interact action against the focused interactable and fire
+/// [`InteractEvent`]s.
+pub fn detect_interaction(
+    mut player_query: Query<(Entity, &mut InteractProgress), With<Player>>,
+    focused: Query<(Entity, &Interactable), With<FocusTarget>>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut ev_interact: MessageWriter<InteractEvent>,
+    time: Res<Time>,
+) {
+    let gamepad = gamepad_query.single().ok();
+    let pressed = bindings.action_interact.just_pressed(&keys, gamepad);
+    let held = bindings.action_interact.pressed(&keys, gamepad);
+    let target = focused
+        .iter()
+        .find(|(_, interactable)| interactable.enabled);
+    for (player, mut progress) in &mut player_query {
+        let Some((entity, interactable)) = target else {
+            *progress = InteractProgress::default();
+            continue;
+        };
+        if progress.target != Some(entity) {
+            *progress = InteractProgress {
+                target: Some(entity),
+                ..Default::default()
+            };
+        }
+        if !held {
+            progress.elapsed = 0.0;
+            progress.fired = false;
+            continue;
+        }
+
+        let fire = if interactable.hold_time <= 0.0 {
+            pressed
+        } else if !progress.fired {
+            progress.elapsed += time.delta_secs();
+            progress.elapsed >= interactable.hold_time
+        } else {
+            false
+        };
+        if fire {
+            progress.fired = true;
+            info!("Interact: {} {}", interactable.verb.label(), entity);
+            ev_interact.write(InteractEvent {
+                player,
+                target: entity,
+                verb: interactable.verb,
+            });
+        }
+    }
+}
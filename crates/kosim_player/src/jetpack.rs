@@ -0,0 +1,185 @@
+//! Jetpack flight and zero-g EVA.
+//!
+//! Once airborne, releasing and pressing jump again fires the jetpack
+//! ([`LocomotionState::Fly`]): it pushes along the local up while jump is held, and
+//! along the camera's forward/right with the movement input. Beyond
+//! [`PlayerControlConfig::eva_distance`] from the planet centre the player is in
+//! [`LocomotionState::Eva`]: gravity no longer applies, the body is no longer kept
+//! radially aligned, and the movement input, jump and descend thrust in the camera's
+//! frame. Both burn [`Jetpack::fuel`], which refills on the ground.
+
+use avian3d::prelude::{ConstantForce, Mass};
+use bevy::{
+    ecs::{
+        component::Component,
+        query::With,
+        system::{Query, Res},
+    },
+    input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
+    math::Vec3,
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
+use kosim_camera::{GameCamera, mode::CameraModeState};
+use kosim_input::{binding::Bindings, input::Input};
+
+use crate::{
+    Player,
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+    locomotion::{Locomotion, LocomotionState},
+    stance::{Stance, StanceType},
+};
+
+/// How far back inside `eva_distance` the player must come before EVA ends, so
+/// hovering at the boundary doesn't flip gravity on and off.
+const EVA_HYSTERESIS: f32 = 10.0;
+
+#[derive(Component)]
+pub struct Jetpack {
+    pub fuel: f32,
+    pub max_fuel: f32,
+    /// Jump was released since leaving the ground, so holding it again fires the
+    /// jetpack rather than extending the jump.
+    pub armed: bool,
+    /// The jetpack is firing this tick.
+    pub thrusting: bool,
+    /// Far enough from the planet for zero-g EVA.
+    pub in_eva: bool,
+}
+
+impl Jetpack {
+    pub fn new(max_fuel: f32) -> Self {
+        Self {
+            fuel: max_fuel,
+            max_fuel,
+            armed: false,
+            thrusting: false,
+            in_eva: false,
+        }
+    }
+}
+
+/// Run condition: the first-person camera keeps its own pitch except in EVA, where
+/// the whole body pitches instead.
+pub fn player_not_in_eva(query: Query<&Locomotion, With<Player>>) -> bool {
+    query
+        .iter()
+        .all(|locomotion| locomotion.current != LocomotionState::Eva)
+}
+
+/// Read the jetpack input, refuel on the ground, and work out whether the player is
+/// thrusting and whether they are far enough out for EVA. Input is ignored while the
+/// camera is off the player, but the rest keeps running so the jetpack cuts out.
+pub fn update_jetpack(
+    mut query: Query<(&mut Jetpack, &Transform, &Stance), With<Player>>,
+    config: Res<PlayerControlConfig>,
+    gravity: Res<PlanetGravity>,
+    input: Res<Input>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    camera_mode: Res<CameraModeState>,
+    time: Res<Time>,
+) {
+    let control = camera_mode.player_control();
+    let held = control
+        && bindings
+            .action_jump
+            .pressed(&keys, gamepad_query.single().ok());
+    for (mut jetpack, transform, stance) in &mut query {
+        let distance = transform.translation.distance(gravity.center);
+        jetpack.in_eva = if jetpack.in_eva {
+            distance > config.eva_distance - EVA_HYSTERESIS
+        } else {
+            distance > config.eva_distance
+        };
+
+        if stance.current != StanceType::Airborne {
+            jetpack.armed = false;
+            jetpack.thrusting = false;
+            jetpack.fuel = (jetpack.fuel + config.jetpack_fuel_regen * time.delta_secs())
+                .min(jetpack.max_fuel);
+            continue;
+        }
+        if !held {
+            jetpack.armed = true;
+        }
+        let steering = control
+            && (input.movement_raw.length_squared() > 0.0 || keys.pressed(bindings.move_descend));
+        jetpack.thrusting = jetpack.fuel > 0.0
+            && if jetpack.in_eva {
+                held || steering
+            } else {
+                jetpack.armed && held
+            };
+    }
+}
+
+/// Add the jetpack's thrust on top of the ride spring / gravity force, or replace
+/// gravity entirely in EVA. Runs after `apply_standing_spring_force`.
+pub fn apply_jetpack_thrust(
+    mut query: Query<
+        (
+            &mut Jetpack,
+            &mut ConstantForce,
+            &Transform,
+            &Mass,
+            &Locomotion,
+        ),
+        With<Player>,
+    >,
+    camera_query: Query<&GlobalTransform, With<GameCamera>>,
+    config: Res<PlayerControlConfig>,
+    gravity: Res<PlanetGravity>,
+    input: Res<Input>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    time: Res<Time>,
+) {
+    let held = bindings
+        .action_jump
+        .pressed(&keys, gamepad_query.single().ok());
+    let descend = keys.pressed(bindings.move_descend);
+    for (mut jetpack, mut constant_force, transform, mass, locomotion) in &mut query {
+        let eva = locomotion.current == LocomotionState::Eva;
+        if eva {
+            // Out of the planet's pull.
+            constant_force.0 = Vec3::ZERO;
+        }
+        if !jetpack.thrusting || !(eva || locomotion.current == LocomotionState::Fly) {
+            continue;
+        }
+
+        // Thrust in the camera's frame; fall back to the body's if it is detached.
+        let frame = camera_query
+            .single()
+            .map(|camera| camera.compute_transform())
+            .unwrap_or(*transform);
+        let forward = frame.forward().as_vec3();
+        let right = frame.right().as_vec3();
+        let steer = forward * input.movement_raw.z + right * input.movement_raw.x;
+
+        let acceleration = if eva {
+            let up = frame.up().as_vec3();
+            let vertical = (held as i32 - descend as i32) as f32;
+            (steer + up * vertical).clamp_length_max(1.0) * config.eva_thrust_acceleration
+        } else {
+            let up = up_at(transform.translation, gravity.center);
+            // Steer along the ground plane; lift comes from the up thrust.
+            let steer = (steer - up * steer.dot(up)).clamp_length_max(1.0);
+            up * config.jetpack_up_acceleration + steer * config.jetpack_lateral_acceleration
+        };
+        constant_force.0 += acceleration * mass.0;
+
+        // Burn in proportion to how hard the jetpack is working.
+        let throttle = if eva {
+            acceleration.length() / config.eva_thrust_acceleration
+        } else {
+            1.0
+        };
+        jetpack.fuel =
+            (jetpack.fuel - config.jetpack_fuel_burn * throttle * time.delta_secs()).max(0.0);
+    }
+}
@@ -0,0 +1,35 @@
+//! Collision layers for the player and what it carries.
+//!
+//! Everything that does not set its own [`CollisionLayers`] lives on
+//! [`GameLayer::Default`]. The player sits on its own layer so that a body it is
+//! carrying ([`GameLayer::Carried`], see [`crate::carry`]) still collides with the
+//! world but never with the player, and the player's own probes look through it.
+
+use avian3d::prelude::{CollisionLayers, PhysicsLayer, SpatialQueryFilter};
+use bevy::ecs::entity::Entity;
+
+#[derive(PhysicsLayer, Default, Clone, Copy, Debug)]
+pub enum GameLayer {
+    #[default]
+    Default,
+    Player,
+    Carried,
+}
+
+/// The player's layers: collides with everything except carried bodies.
+pub fn player_layers() -> CollisionLayers {
+    CollisionLayers::new(GameLayer::Player, [GameLayer::Default, GameLayer::Player])
+}
+
+/// Layers for a body while it is carried: it keeps colliding with the world (and
+/// other carried bodies) but not with the player.
+pub fn carried_layers() -> CollisionLayers {
+    CollisionLayers::new(GameLayer::Carried, [GameLayer::Default, GameLayer::Carried])
+}
+
+/// Filter for the player's own shape casts: skips the player itself and anything
+/// it is carrying.
+pub fn player_query_filter(entity: Entity) -> SpatialQueryFilter {
+    SpatialQueryFilter::from_excluded_entities([entity])
+        .with_mask([GameLayer::Default, GameLayer::Player])
+}
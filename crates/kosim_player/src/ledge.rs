@@ -0,0 +1,346 @@
+//! Step-up and ledge mantling.
+//!
+//! Both start from the same probe ([`find_ledge`]): a small sphere swept forward
+//! just above the feet finds a wall, then a second sweep straight down from above
+//! the wall finds its top. Everything is measured along the radial `up`.
+//!
+//! - Ledges up to [`PlayerControlConfig::max_step_height`] are stepped onto while
+//!   walking: the body is lifted just enough for the capsule to clear the edge, and
+//!   the ride spring picks up the new ground from there.
+//! - Ledges up to [`PlayerControlConfig::max_mantle_height`] (about chest height)
+//!   can be mantled by pressing jump while facing them. The player is pulled up and
+//!   over it across [`PlayerControlConfig::mantle_duration`] in
+//!   [`LocomotionState::Mantle`].
+
+use avian3d::prelude::{
+    Collider, ConstantForce, LinearVelocity, ShapeCastConfig, ShapeHitData, SpatialQuery,
+    SpatialQueryFilter,
+};
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        message::MessageWriter,
+        query::{With, Without},
+        system::{Commands, Query, Res},
+    },
+    input::{ButtonInput, gamepad::Gamepad, keyboard::KeyCode},
+    log::info,
+    math::{Dir3, Quat, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+
+use kosim_input::binding::Bindings;
+
+use crate::{
+    Player,
+    body::{Body, GROUND_PROBE_RADIUS, StandingSpringForce},
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+    layers::player_query_filter,
+    locomotion::{Locomotion, LocomotionChanged, LocomotionState},
+    motion::Motion,
+    stance::Stance,
+};
+
+/// Radius of the spheres used to find walls and ledge tops.
+const LEDGE_PROBE_RADIUS: f32 = 0.2;
+/// How far past the capsule's front the wall probe reaches.
+const LEDGE_REACH: f32 = 0.3;
+/// Obstacles lower than this are left to the ride spring.
+const LEDGE_MIN_HEIGHT: f32 = 0.05;
+/// Extra lift on a step-up so the capsule clears the edge instead of grazing it.
+const STEP_MARGIN: f32 = 0.05;
+
+/// A ledge in front of the player.
+pub struct Ledge {
+    /// Height of the ledge top above the player's feet.
+    pub height: f32,
+    /// How far forward of the player the ledge top starts.
+    pub depth: f32,
+}
+
+/// An in-progress mantle: the body is moved along a fixed path and physics is held off.
+#[derive(Component)]
+pub struct Mantling {
+    start: Vec3,
+    rise: Vec3,
+    advance: Vec3,
+    elapsed: f32,
+}
+
+fn cast(
+    spatial_query: &SpatialQuery,
+    filter: &SpatialQueryFilter,
+    radius: f32,
+    origin: Vec3,
+    direction: Vec3,
+    distance: f32,
+) -> Option<ShapeHitData> {
+    let direction = Dir3::new(direction).ok()?;
+    spatial_query.cast_shape(
+        &Collider::sphere(radius),
+        origin,
+        Quat::IDENTITY,
+        direction,
+        &ShapeCastConfig::from_max_distance(distance),
+        filter,
+    )
+}
+
+/// Look for a ledge at most `max_height` above the feet (`ground_distance` below
+/// `origin`) in the tangential direction `forward`. Only a wall steeper than the
+/// walkable slope counts, and only a top shallow enough to stand on.
+pub fn find_ledge(
+    spatial_query: &SpatialQuery,
+    filter: &SpatialQueryFilter,
+    origin: Vec3,
+    up: Vec3,
+    forward: Vec3,
+    ground_distance: f32,
+    max_height: f32,
+    config: &PlayerControlConfig,
+) -> Option<Ledge> {
+    let walkable_cos = config.max_walkable_slope_angle.to_radians().cos();
+    let feet = origin - up * ground_distance;
+
+    let wall = cast(
+        spatial_query,
+        filter,
+        LEDGE_PROBE_RADIUS,
+        feet + up * (LEDGE_MIN_HEIGHT + LEDGE_PROBE_RADIUS),
+        forward,
+        GROUND_PROBE_RADIUS + LEDGE_REACH,
+    )?;
+    if (-wall.normal1).dot(up) >= walkable_cos {
+        // A walkable slope, not a wall; the ride spring handles it.
+        return None;
+    }
+
+    let depth = wall.distance + LEDGE_PROBE_RADIUS * 2.0;
+    let top = cast(
+        spatial_query,
+        filter,
+        LEDGE_PROBE_RADIUS,
+        feet + up * (max_height + LEDGE_PROBE_RADIUS) + forward * depth,
+        -up,
+        max_height,
+    )?;
+    // A sweep that starts inside geometry means the wall is taller than `max_height`.
+    if top.distance <= 0.0 || (-top.normal1).dot(up) < walkable_cos {
+        return None;
+    }
+    let height = max_height - top.distance;
+    (height >= LEDGE_MIN_HEIGHT).then_some(Ledge { height, depth })
+}
+
+/// Lift a walking player onto ledges no taller than [`PlayerControlConfig::max_step_height`].
+pub fn step_up(
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Motion,
+            &Body,
+            &StandingSpringForce,
+            &Locomotion,
+        ),
+        With<Player>,
+    >,
+    spatial_query: SpatialQuery,
+    gravity: Res<PlanetGravity>,
+    config: Res<PlayerControlConfig>,
+) {
+    for (entity, mut transform, motion, body, standing_spring, locomotion) in &mut query {
+        if !motion.moving
+            || !locomotion.current.is_grounded()
+            || locomotion.current == LocomotionState::Slide
+        {
+            continue;
+        }
+        let up = up_at(transform.translation, gravity.center);
+        let forward = motion.movement_vector.current;
+        let forward = (forward - up * forward.dot(up)).normalize_or_zero();
+        let filter = player_query_filter(entity);
+        let ride_height = standing_spring.length.current;
+        let Some(ledge) = find_ledge(
+            &spatial_query,
+            &filter,
+            transform.translation,
+            up,
+            forward,
+            ride_height,
+            config.max_step_height,
+            &config,
+        ) else {
+            continue;
+        };
+
+        // The capsule's bottom already floats this far above the feet.
+        let clearance = ride_height - (body.current_body_height * 0.5 + GROUND_PROBE_RADIUS);
+        let lift = ledge.height - clearance + STEP_MARGIN;
+        if lift <= 0.0 {
+            continue;
+        }
+        // Only step if the capsule has room to rise.
+        if cast(
+            &spatial_query,
+            &filter,
+            GROUND_PROBE_RADIUS * 0.9,
+            transform.translation,
+            up,
+            lift,
+        )
+        .is_some()
+        {
+            continue;
+        }
+        transform.translation += up * lift;
+    }
+}
+
+/// Start a mantle when jump is pressed facing a ledge within reach.
+pub fn detect_mantle(
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &Stance,
+            &mut Locomotion,
+            &StandingSpringForce,
+        ),
+        (With<Player>, Without<Mantling>),
+    >,
+    spatial_query: SpatialQuery,
+    gravity: Res<PlanetGravity>,
+    config: Res<PlayerControlConfig>,
+    gamepad_query: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    mut commands: Commands,
+    mut ev_locomotion: MessageWriter<LocomotionChanged>,
+) {
+    if !bindings
+        .action_jump
+        .pressed(&keys, gamepad_query.single().ok())
+    {
+        return;
+    }
+    for (entity, transform, stance, mut locomotion, standing_spring) in &mut query {
+        if stance.crouched
+            || !locomotion
+                .current
+                .can_transition_to(LocomotionState::Mantle)
+        {
+            continue;
+        }
+        let up = up_at(transform.translation, gravity.center);
+        let forward = transform.forward().as_vec3();
+        let forward = (forward - up * forward.dot(up)).normalize_or_zero();
+        let filter = player_query_filter(entity);
+        let ride_height = standing_spring.length.current;
+        let Some(ledge) = find_ledge(
+            &spatial_query,
+            &filter,
+            transform.translation,
+            up,
+            forward,
+            ride_height,
+            config.max_mantle_height,
+            &config,
+        ) else {
+            continue;
+        };
+        // Low ledges are stepped onto while walking; jumping on them is just a jump.
+        if locomotion.current.is_grounded() && ledge.height <= config.max_step_height {
+            continue;
+        }
+        // Room to rise to the ledge, and then to move over it.
+        let rise = up * ledge.height;
+        let advance = forward * (ledge.depth + GROUND_PROBE_RADIUS);
+        if cast(
+            &spatial_query,
+            &filter,
+            GROUND_PROBE_RADIUS * 0.9,
+            transform.translation,
+            up,
+            ledge.height,
+        )
+        .is_some()
+            || cast(
+                &spatial_query,
+                &filter,
+                GROUND_PROBE_RADIUS * 0.9,
+                transform.translation + rise,
+                forward,
+                advance.length(),
+            )
+            .is_some()
+        {
+            continue;
+        }
+
+        let Ok(from) =
+            locomotion.try_transition(LocomotionState::Mantle, Some(config.mantle_duration))
+        else {
+            continue;
+        };
+        ev_locomotion.write(LocomotionChanged {
+            entity,
+            from,
+            to: LocomotionState::Mantle,
+        });
+        info!("Mantling onto a {:.2} high ledge", ledge.height);
+        commands.entity(entity).insert(Mantling {
+            start: transform.translation,
+            rise,
+            advance,
+            elapsed: 0.0,
+        });
+    }
+}
+
+/// Move mantling players along their path: up over the first half, forward over
+/// the second. Runs after the ride spring so it can hold the physics off.
+pub fn drive_mantle(
+    mut query: Query<
+        (
+            Entity,
+            &mut Mantling,
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut ConstantForce,
+            &mut Motion,
+        ),
+        With<Player>,
+    >,
+    config: Res<PlayerControlConfig>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (
+        entity,
+        mut mantling,
+        mut transform,
+        mut linear_velocity,
+        mut constant_force,
+        mut motion,
+    ) in &mut query
+    {
+        mantling.elapsed += time.delta_secs();
+        let t = (mantling.elapsed / config.mantle_duration).clamp(0.0, 1.0);
+        let ease = |x: f32| x * x * (3.0 - 2.0 * x);
+        transform.translation = mantling.start
+            + mantling.rise * ease((t * 2.0).min(1.0))
+            + mantling.advance * ease((t * 2.0 - 1.0).max(0.0));
+
+        linear_velocity.0 = Vec3::ZERO;
+        constant_force.0 = Vec3::ZERO;
+        motion.linear_velocity_interp.current = Vec3::ZERO;
+        motion.linear_velocity_interp.target = Vec3::ZERO;
+        if t >= 1.0 {
+            commands.entity(entity).remove::<Mantling>();
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use avian3d::prelude::*;
 use bevy::{
     app::{App, FixedUpdate, Plugin, Startup, Update},
-    asset::Assets,
+    asset::{AssetApp, Assets},
     camera::{Camera, Camera3d},
     color::Color,
     ecs::{
@@ -10,7 +10,7 @@ use bevy::{
         entity::Entity,
         hierarchy::ChildOf,
         query::{With, Without},
-        schedule::IntoScheduleConfigs,
+        schedule::{IntoScheduleConfigs, SystemCondition},
         system::{Commands, Query, Res, ResMut},
     },
     input::{gamepad::GamepadButton, keyboard::KeyCode},
@@ -24,20 +24,28 @@ use bevy::{
 use bevy_enhanced_input::{
     EnhancedInputPlugin, action::Action, actions, bindings, prelude::InputContextAppExt,
 };
-use kosim_camera::GameCamera;
-use kosim_utility::interpolated_value::InterpolatedValue;
+use kosim_camera::{
+    GameCamera,
+    mode::{CameraTarget, free_cam_active, player_control_active, switch_camera_mode},
+    path::camera_path_idle,
+};
+use kosim_utility::{interpolated_value::InterpolatedValue, toml_file::TomlFileLoader};
+use kosim_world::ChunkObserver;
 
 use crate::{
     action::{
-        ACTION_STEP_DELTA_DEFAULT, ActionStep, Crouch, FootstepDirection, FootstepEvent, Sprint,
-        detect_action_crouching, detect_action_jumping, detect_action_sprinting, load_footstep_sfx,
-        play_footstep_sfx, tick_footstep,
+        ACTION_STEP_DELTA_DEFAULT, ActionStep, Crouch, FootstepDirection, FootstepEvent,
+        JumpControl, Sprint, detect_action_crouching, detect_action_jumping,
+        detect_action_sprinting, latch_jump_press, load_footstep_sfx, play_footstep_sfx,
+        tick_footstep, update_jump_control,
     },
     body::{
-        Body, GROUND_PROBE_RADIUS, IgnoreRayCollision, StandingSpringForce,
-        apply_standing_spring_force, lock_angular_velocity,
+        Body, GROUND_PROBE_RADIUS, IgnoreRayCollision, StandingSpringForce, VoxelGround,
+        apply_standing_spring_force, lock_angular_velocity, probe_voxel_ground,
     },
-    config::PlayerControlConfig,
+    carry::{drive_carried, grab_or_release, player_not_rotating_carried},
+    climb::{detect_climb, drive_climb},
+    config::{PlayerControlConfig, apply_player_config, load_player_config},
     debug::{
         create_player_debug, update_debug_is_moving, update_debug_is_sprinting,
         update_debug_linear_velocity, update_debug_movement_speed_current,
@@ -46,27 +54,56 @@ use crate::{
         update_debug_position, update_debug_rotation,
     },
     focus::{Focus, update_focus_target, camera_look_system},
+    health::{
+        DamageEvent, Died, HealEvent, Health, HealthChanged, PlayerSpawnPoint, Respawned,
+        apply_health_events, player_alive, respawn_dead_players,
+    },
+    interact::{InteractEvent, InteractProgress, detect_interaction},
+    jetpack::{Jetpack, apply_jetpack_thrust, player_not_in_eva, update_jetpack},
+    layers::{GameLayer, player_layers},
+    ledge::{detect_mantle, drive_mantle, step_up},
+    locomotion::{Locomotion, LocomotionChanged, run_locomotion_hooks, update_locomotion},
     motion::{Motion, TouchedEntities, player_motion_system, player_rotation_system, run_move_and_slide},
+    platform::{GroundMotion, update_ground_motion},
+    push::push_touched_bodies,
+    slope::{GroundSlope, update_ground_slope},
+    stamina::{Breathing, Stamina, load_breathing_sfx, play_breathing_sfx, update_stamina},
     stance::{Stance, StanceType, compute_next_stance},
 };
 
 pub mod action;
 pub mod body;
+pub mod carry;
+pub mod climb;
 pub mod config;
 pub mod debug;
 pub mod focus;
 pub mod freecam;
 pub mod gravity;
+pub mod health;
+pub mod interact;
+pub mod jetpack;
+pub mod layers;
+pub mod ledge;
+pub mod locomotion;
 pub mod motion;
+pub mod platform;
+pub mod push;
+pub mod slope;
+pub mod stamina;
 pub mod stance;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(PlayerControlConfig::default()); // later we will load from some toml file
+        // Defaults until `config/player.toml` loads; edits to it are hot reloaded.
+        app.insert_resource(PlayerControlConfig::default())
+            .init_asset::<PlayerControlConfig>()
+            .register_asset_loader(TomlFileLoader::<PlayerControlConfig>::default());
         app.init_resource::<crate::freecam::FreeCam>();
         app.init_resource::<crate::gravity::PlanetGravity>();
+        app.init_resource::<PlayerSpawnPoint>();
         // Point gravity: disable Avian's global (down) gravity; the player is pulled
         // radially toward the planet centre by `apply_standing_spring_force`.
         app.insert_resource(Gravity(Vec3::ZERO));
@@ -75,29 +112,80 @@ impl Plugin for PlayerPlugin {
         app.add_systems(
             Startup,
             (
+                load_player_config,
                 spawn_player,
                 load_footstep_sfx,
+                load_breathing_sfx,
                 attached_camera_system,
                 create_player_debug,
             )
                 .chain(),
         );
-        app.add_systems(Update, crate::freecam::toggle_free_cam);
+        app.add_systems(
+            Update,
+            (
+                crate::freecam::seed_free_cam.after(switch_camera_mode),
+                crate::freecam::adjust_free_cam.run_if(free_cam_active),
+                apply_player_config,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (detect_interaction.run_if(player_alive), grab_or_release)
+                .chain()
+                .run_if(player_control_active),
+        );
+        app.add_systems(Update, latch_jump_press.run_if(player_control_active));
         app.add_systems(
             FixedUpdate,
             (
-                camera_look_system.run_if(crate::freecam::player_control_active),
-                player_rotation_system.run_if(crate::freecam::player_control_active),
-                player_motion_system.run_if(crate::freecam::player_control_active),
-                run_move_and_slide.run_if(crate::freecam::player_control_active),
-                crate::freecam::free_cam_control.run_if(crate::freecam::free_cam_active),
-                compute_next_stance,
-                detect_action_jumping,
-                detect_action_crouching,
-                detect_action_sprinting,
-                apply_standing_spring_force,
+                camera_look_system.run_if(
+                    player_control_active
+                        .and(player_not_in_eva)
+                        .and(player_not_rotating_carried),
+                ),
+                player_rotation_system
+                    .run_if(player_control_active.and(player_not_rotating_carried)),
+                player_motion_system.run_if(player_control_active.and(player_alive)),
+                (run_move_and_slide, push_touched_bodies)
+                    .chain()
+                    .run_if(player_control_active),
+                step_up.run_if(player_control_active),
+                (
+                    crate::freecam::teleport_player_to_free_cam,
+                    crate::freecam::free_cam_control.run_if(camera_path_idle),
+                )
+                    .chain()
+                    .run_if(free_cam_active),
+                probe_voxel_ground,
+                update_ground_slope,
+                (compute_next_stance, update_ground_motion).chain(),
+                (apply_health_events, respawn_dead_players).chain(),
+                (update_jetpack, update_locomotion).chain(),
+                (
+                    update_jump_control,
+                    detect_mantle.run_if(player_alive.and(player_control_active)),
+                    detect_climb.run_if(player_alive.and(player_control_active)),
+                    detect_action_jumping.run_if(player_alive.and(player_control_active)),
+                    run_locomotion_hooks,
+                )
+                    .chain(),
+                detect_action_crouching.run_if(player_alive.and(player_control_active)),
+                (
+                    detect_action_sprinting.run_if(player_alive.and(player_control_active)),
+                    update_stamina,
+                )
+                    .chain(),
+                (
+                    apply_standing_spring_force,
+                    apply_jetpack_thrust.run_if(player_alive),
+                    drive_mantle,
+                    drive_climb,
+                    drive_carried,
+                )
+                    .chain(),
                 lock_angular_velocity,
-                play_footstep_sfx,
+                (play_footstep_sfx, play_breathing_sfx),
                 tick_footstep,
                 update_focus_target,
             )
@@ -121,6 +209,13 @@ impl Plugin for PlayerPlugin {
                 .chain(),
         );
         app.add_message::<FootstepEvent>();
+        app.add_message::<LocomotionChanged>();
+        app.add_message::<DamageEvent>();
+        app.add_message::<HealEvent>();
+        app.add_message::<HealthChanged>();
+        app.add_message::<Died>();
+        app.add_message::<Respawned>();
+        app.add_message::<InteractEvent>();
         // info!("Initialized Player plugin");
     }
 }
@@ -135,12 +230,22 @@ pub struct PlayerBundle {
     impulse_force: ConstantLinearAcceleration,
     ground_caster: ShapeCaster,
     ground_hits: ShapeHits,
+    voxel_ground: VoxelGround,
+    ground_slope: GroundSlope,
+    ground_motion: GroundMotion,
+    interact_progress: InteractProgress,
     body: Body,
     motion: Motion,
     focus: Focus,
     stance: Stance,
+    locomotion: Locomotion,
     standing_spring_force: StandingSpringForce,
     action_step: ActionStep,
+    jump_control: JumpControl,
+    health: Health,
+    stamina: Stamina,
+    breathing: Breathing,
+    jetpack: Jetpack,
     mass: Mass,
     locked_axes: LockedAxes,
     gravity_scale: GravityScale,
@@ -169,14 +274,23 @@ pub fn spawn_player(
                 // of a thin ray, so the body floats clear of the tallest surface
                 // under its whole footprint (see `GROUND_PROBE_RADIUS`). Defaults
                 // are max_hits = 1 and ignore_self = true, so this returns just
-                // the closest ground hit below the player.
+                // the closest ground hit below the player. The mask looks through
+                // whatever the player is carrying.
                 ground_caster: ShapeCaster::new(
                     Collider::sphere(GROUND_PROBE_RADIUS),
                     Vec3::ZERO,
                     Quat::IDENTITY,
                     Dir3::NEG_Y,
-                ),
+                )
+                .with_query_filter(SpatialQueryFilter::from_mask([
+                    GameLayer::Default,
+                    GameLayer::Player,
+                ])),
                 ground_hits: ShapeHits::default(),
+                voxel_ground: VoxelGround::default(),
+                ground_slope: GroundSlope::default(),
+                ground_motion: GroundMotion::default(),
+                interact_progress: InteractProgress::default(),
                 rigid_body: RigidBody::Dynamic,
                 locked_axes: LockedAxes::new()
                     .lock_rotation_z()
@@ -193,7 +307,7 @@ pub fn spawn_player(
                     ),
                     movement_vector: InterpolatedValue::new(
                         Vec3::from_array([0.0, 0.0, 0.0]),
-                        16.0,
+                        player_config.movement_decay,
                     ),
                     movement_speed: InterpolatedValue::new(
                         player_config.default_movement_speed,
@@ -205,14 +319,20 @@ pub fn spawn_player(
                 stance: Stance {
                     current: StanceType::Standing,
                     crouched: false,
-                    lockout_timer: 0.0,
+                    crouch_requested: false,
                 },
+                locomotion: Locomotion::default(),
                 focus: Focus {},
                 action_step: ActionStep {
                     dir: FootstepDirection::Right,
                     delta: ACTION_STEP_DELTA_DEFAULT,
                     bumped: false,
                 },
+                jump_control: JumpControl::default(),
+                health: Health::new(player_config.max_health),
+                stamina: Stamina::new(player_config.max_stamina),
+                breathing: Breathing::default(),
+                jetpack: Jetpack::new(player_config.jetpack_max_fuel),
                 standing_spring_force: StandingSpringForce {
                     length: InterpolatedValue::new(player_config.ride_height, 6.0),
                     extension: player_config.ray_length_offset,
@@ -230,8 +350,13 @@ pub fn spawn_player(
             TouchedEntities::default(),
             CollidingEntities::default(),
             Collider::capsule(0.5, 1.0),
+            player_layers(),
             IgnoreRayCollision,
             Player,
+            CameraTarget,
+            // Stream terrain (and its colliders) around the body itself, so it keeps
+            // standing on something while the camera is detached.
+            ChunkObserver::physics(),
             actions!(Player[(Action::<Crouch>::new(), bindings![KeyCode::ControlLeft, GamepadButton::LeftThumb]),(
                 Action::<Sprint>::new(), bindings![KeyCode::ShiftLeft, GamepadButton::South])])
         ));
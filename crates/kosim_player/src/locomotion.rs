@@ -0,0 +1,349 @@
+//! The player's locomotion state machine.
+//!
+//! [`Stance`] stays the low-level ground-contact sensor (Airborne/Standing/Landing,
+//! from the ride-spring probe) and [`Motion`] carries the movement input flags; this
+//! module folds them into one [`LocomotionState`] per frame. Every state declares
+//! which states it may move to, a change is announced as a [`LocomotionChanged`]
+//! message, and [`run_locomotion_hooks`] runs the exit/entry behaviour for it.
+//!
+//! A lockout ([`Locomotion::lockout`]) holds the current state for a while, e.g. the
+//! first moments of a jump while the ground probe still reads contact.
+
+use std::fmt;
+
+use avian3d::prelude::LinearVelocity;
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::Event,
+        message::{Message, MessageReader, MessageWriter},
+        query::{Has, With},
+        system::{Query, Res},
+    },
+    log::{info, trace},
+    time::Time,
+    transform::components::Transform,
+};
+
+use crate::{
+    Player,
+    action::{DEFAULT_STEP_VOLUME, FootstepDirection, FootstepEvent},
+    climb::Climbing,
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+    jetpack::Jetpack,
+    motion::Motion,
+    slope::GroundSlope,
+    stance::{Stance, StanceType},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum LocomotionState {
+    Idle,
+    Walk,
+    Sprint,
+    Crouch,
+    CrouchWalk,
+    /// Airborne and rising after a jump.
+    Jump,
+    /// Airborne and not rising (falling after a jump, or walked off an edge). A
+    /// coyote-time jump leaves it for `Jump`.
+    Fall,
+    /// The touchdown frame after being airborne.
+    Land,
+    /// Grounded but sliding down a surface too steep to stand on.
+    Slide,
+    /// Reserved for water volumes; nothing enters it yet.
+    Swim,
+    /// Being pulled up onto a ledge (see [`crate::ledge`]).
+    Mantle,
+    /// Airborne under jetpack thrust (see [`crate::jetpack`]).
+    Fly,
+    /// Far enough from the planet that gravity no longer applies and the body turns
+    /// freely (see [`crate::jetpack`]).
+    Eva,
+    /// Attached to a ladder or climbable surface (see [`crate::climb`]).
+    Climb,
+}
+
+impl LocomotionState {
+    /// Standing on the ground (including the touchdown frame).
+    pub fn is_grounded(self) -> bool {
+        !matches!(
+            self,
+            LocomotionState::Jump
+                | LocomotionState::Fall
+                | LocomotionState::Swim
+                | LocomotionState::Mantle
+                | LocomotionState::Fly
+                | LocomotionState::Eva
+                | LocomotionState::Climb
+        )
+    }
+
+    pub fn is_airborne(self) -> bool {
+        matches!(
+            self,
+            LocomotionState::Jump
+                | LocomotionState::Fall
+                | LocomotionState::Fly
+                | LocomotionState::Eva
+        )
+    }
+
+    pub fn is_crouched(self) -> bool {
+        matches!(self, LocomotionState::Crouch | LocomotionState::CrouchWalk)
+    }
+
+    /// The states this one may move to.
+    pub fn transitions(self) -> &'static [LocomotionState] {
+        use LocomotionState::*;
+        match self {
+            Idle => &[
+                Walk, Sprint, Crouch, CrouchWalk, Jump, Fall, Slide, Swim, Mantle, Climb,
+            ],
+            Walk => &[
+                Idle, Sprint, Crouch, CrouchWalk, Jump, Fall, Slide, Swim, Mantle, Climb,
+            ],
+            Sprint => &[
+                Idle, Walk, Crouch, CrouchWalk, Jump, Fall, Slide, Swim, Mantle, Climb,
+            ],
+            Crouch => &[
+                Idle, Walk, Sprint, CrouchWalk, Jump, Fall, Slide, Swim, Mantle, Climb,
+            ],
+            CrouchWalk => &[
+                Idle, Walk, Sprint, Crouch, Jump, Fall, Slide, Swim, Mantle, Climb,
+            ],
+            Jump => &[Fall, Land, Swim, Mantle, Fly, Eva, Climb],
+            Fall => &[Jump, Land, Swim, Mantle, Fly, Eva, Climb],
+            Land => &[
+                Idle, Walk, Sprint, Crouch, CrouchWalk, Jump, Fall, Slide, Mantle, Climb,
+            ],
+            Slide => &[
+                Idle, Walk, Crouch, CrouchWalk, Jump, Fall, Swim, Mantle, Climb,
+            ],
+            Swim => &[Idle, Walk, Fall],
+            Mantle => &[Idle, Walk, Sprint, Crouch, CrouchWalk, Fall, Land, Slide],
+            Fly => &[Fall, Land, Swim, Mantle, Eva, Climb],
+            Eva => &[Fall, Fly],
+            Climb => &[Idle, Walk, Jump, Fall, Mantle],
+        }
+    }
+
+    pub fn can_transition_to(self, next: LocomotionState) -> bool {
+        self.transitions().contains(&next)
+    }
+
+    /// Transitions that go through even while a lockout is running: the lockout
+    /// only holds the body off the ground, it does not freeze it mid-air or stop it
+    /// grabbing a ledge or a ladder.
+    fn ignores_lockout(self, next: LocomotionState) -> bool {
+        (self.is_airborne()
+            && (next.is_airborne()
+                || matches!(next, LocomotionState::Mantle | LocomotionState::Climb)))
+            || next == LocomotionState::Swim
+    }
+}
+
+impl fmt::Display for LocomotionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(Component)]
+pub struct Locomotion {
+    pub current: LocomotionState,
+    pub previous: LocomotionState,
+    /// Seconds spent in `current`.
+    pub time_in_state: f32,
+    /// Seconds until the current state may change again (see
+    /// [`LocomotionState::ignores_lockout`] for the exceptions).
+    pub lockout: f32,
+}
+
+impl Default for Locomotion {
+    fn default() -> Self {
+        Self {
+            current: LocomotionState::Idle,
+            previous: LocomotionState::Idle,
+            time_in_state: 0.0,
+            lockout: 0.0,
+        }
+    }
+}
+
+impl Locomotion {
+    pub fn is_locked_out(&self) -> bool {
+        self.lockout > 0.0
+    }
+
+    /// Move to `next` if the current state allows it and no lockout is holding it,
+    /// optionally starting a new lockout. Returns the state that was left.
+    pub fn try_transition(
+        &mut self,
+        next: LocomotionState,
+        lockout: Option<f32>,
+    ) -> Result<LocomotionState, String> {
+        if !self.current.can_transition_to(next) {
+            return Err(format!(
+                "Cannot change locomotion from {} to {}: not an allowed transition.",
+                self.current, next
+            ));
+        }
+        if self.is_locked_out() && !self.current.ignores_lockout(next) {
+            return Err(format!(
+                "Cannot change locomotion to {} because lockout is still active for {} seconds.",
+                next, self.lockout
+            ));
+        }
+        let from = self.current;
+        self.previous = from;
+        self.current = next;
+        self.time_in_state = 0.0;
+        if let Some(lockout) = lockout {
+            self.lockout = lockout;
+        }
+        Ok(from)
+    }
+}
+
+/// Sent whenever an entity's [`Locomotion`] changes state.
+#[derive(Message, Event, Clone)]
+pub struct LocomotionChanged {
+    pub entity: Entity,
+    pub from: LocomotionState,
+    pub to: LocomotionState,
+}
+
+/// The state the sensors and input flags currently call for.
+fn desired_state(
+    current: LocomotionState,
+    stance: &Stance,
+    motion: &Motion,
+    walkable: bool,
+    radial_speed: f32,
+    jetpack: &Jetpack,
+) -> LocomotionState {
+    match stance.current {
+        StanceType::Airborne => {
+            if jetpack.in_eva {
+                LocomotionState::Eva
+            } else if jetpack.thrusting {
+                LocomotionState::Fly
+            // Only a jump enters `Jump`; it lasts while the body is still rising.
+            } else if current == LocomotionState::Jump && radial_speed > 0.0 {
+                LocomotionState::Jump
+            } else {
+                LocomotionState::Fall
+            }
+        }
+        StanceType::Landing => LocomotionState::Land,
+        StanceType::Standing if !walkable => LocomotionState::Slide,
+        StanceType::Standing => match (stance.crouched, motion.moving, motion.sprinting) {
+            (true, false, _) => LocomotionState::Crouch,
+            (true, true, _) => LocomotionState::CrouchWalk,
+            (false, false, _) => LocomotionState::Idle,
+            (false, true, false) => LocomotionState::Walk,
+            (false, true, true) => LocomotionState::Sprint,
+        },
+    }
+}
+
+/// Tick lockouts and move each player toward the state its sensors call for.
+pub fn update_locomotion(
+    mut query: Query<
+        (
+            Entity,
+            &mut Locomotion,
+            &Stance,
+            &Motion,
+            &GroundSlope,
+            &Transform,
+            &LinearVelocity,
+            &Jetpack,
+            Has<Climbing>,
+        ),
+        With<Player>,
+    >,
+    config: Res<PlayerControlConfig>,
+    gravity: Res<PlanetGravity>,
+    mut ev_changed: MessageWriter<LocomotionChanged>,
+    time: Res<Time>,
+) {
+    for (
+        entity,
+        mut locomotion,
+        stance,
+        motion,
+        slope,
+        transform,
+        linear_velocity,
+        jetpack,
+        climbing,
+    ) in &mut query
+    {
+        locomotion.time_in_state += time.delta_secs();
+        if locomotion.lockout > 0.0 {
+            locomotion.lockout = f32::max(locomotion.lockout - time.delta_secs(), 0.0);
+            if locomotion.lockout <= 0.0 {
+                trace!("Locomotion lockout: RELEASED");
+            }
+        }
+        // `crate::climb` owns the state while the player is attached.
+        if climbing {
+            continue;
+        }
+
+        let up = up_at(transform.translation, gravity.center);
+        let next = desired_state(
+            locomotion.current,
+            stance,
+            motion,
+            slope.is_walkable(&config),
+            linear_velocity.0.dot(up),
+            jetpack,
+        );
+        if next == locomotion.current {
+            continue;
+        }
+        match locomotion.try_transition(next, None) {
+            Ok(from) => {
+                ev_changed.write(LocomotionChanged {
+                    entity,
+                    from,
+                    to: next,
+                });
+            }
+            Err(reason) => trace!("{}", reason),
+        }
+    }
+}
+
+/// Exit and entry behaviour for each announced state change.
+pub fn run_locomotion_hooks(
+    mut ev_changed: MessageReader<LocomotionChanged>,
+    mut ev_footstep: MessageWriter<FootstepEvent>,
+) {
+    for change in ev_changed.read() {
+        info!("Locomotion Changed: {} -> {}", change.from, change.to);
+        on_exit(change.from);
+        on_enter(change.to, &mut ev_footstep);
+    }
+}
+
+fn on_exit(state: LocomotionState) {
+    trace!("Locomotion exit: {}", state);
+}
+
+fn on_enter(state: LocomotionState, ev_footstep: &mut MessageWriter<FootstepEvent>) {
+    if state == LocomotionState::Land {
+        // This is the sound effect that plays when the player has jumped or fallen and will land with both feet on the ground.
+        // this effect will play centered and will not pan in any direction.
+        ev_footstep.write(FootstepEvent {
+            dir: FootstepDirection::None,
+            volume: DEFAULT_STEP_VOLUME,
+        });
+    }
+}
@@ -1,12 +1,12 @@
 use avian3d::{math::{AdjustPrecision, AsF32}, prelude::{
-    Collider, ConstantForce, LinearVelocity, MoveAndSlide, MoveAndSlideConfig, MoveAndSlideHitResponse, MoveAndSlideOutput, SpatialQueryFilter
+    Collider, ConstantForce, LinearVelocity, MoveAndSlide, MoveAndSlideConfig, MoveAndSlideHitResponse, MoveAndSlideOutput
 }};
 use bevy::{
     color::palettes::tailwind, ecs::{
         component::Component, entity::{Entity, EntityHashSet}, query::With, system::{Query, Res}
     }, gizmos::gizmos::Gizmos, input::{ButtonInput, keyboard::KeyCode}, log::{trace, warn}, math::{Quat, Vec3}, prelude::{Deref, DerefMut}, time::Time, transform::components::Transform
 };
-use kosim_input::input::Input;
+use kosim_input::{binding::Bindings, input::Input};
 use kosim_utility::{
     exp_decay,
     format_value::{format_value_f32, format_value_vec3},
@@ -17,9 +17,20 @@ use crate::{
     Player,
     config::PlayerControlConfig,
     gravity::{PlanetGravity, up_at},
+    layers::player_query_filter,
+    locomotion::{Locomotion, LocomotionState},
+    platform::GroundMotion,
+    slope::{GroundSlope, slope_speed_factor, tangent_downhill, without_uphill},
     stance::{Stance, StanceType},
 };
 
+/// How strongly movement input steers a slide, relative to walking.
+const SLIDE_STEER_FACTOR: f32 = 0.5;
+/// Below this cosine between the body's up and the radial up (after EVA), the body
+/// is swung back upright at `EVA_REALIGN_RATE` rather than snapped.
+const EVA_REALIGN_THRESHOLD: f32 = 0.999;
+const EVA_REALIGN_RATE: f32 = 4.0;
+
 #[derive(Component)]
 pub struct Motion {
     pub linear_velocity_interp: InterpolatedValue<Vec3>,
@@ -31,7 +42,15 @@ pub struct Motion {
 
 pub fn player_motion_system(
     mut player_query: Query<
-        (&mut LinearVelocity, &mut Transform, &mut Motion, &Stance),
+        (
+            &mut LinearVelocity,
+            &mut Transform,
+            &mut Motion,
+            &Stance,
+            &Locomotion,
+            &GroundSlope,
+            &GroundMotion,
+        ),
         With<Player>,
     >,
     player_config: Res<PlayerControlConfig>,
@@ -47,7 +66,7 @@ pub fn player_motion_system(
         return;
     }
 
-    let (mut linear_velocity, player_transform, mut motion, stance) =
+    let (mut linear_velocity, player_transform, mut motion, stance, locomotion, slope, ground) =
         player_query.single_mut().expect("We do some errors");
 
     // * - COMPUTE CURRENT MOVEMENT SPEED AND LERP -
@@ -116,8 +135,42 @@ pub fn player_motion_system(
     let up = up_at(player_transform.translation, gravity.center);
     let radial_speed = linear_velocity.0.dot(up);
 
-    if stance.current == StanceType::Standing {
-        let target = motion.movement_vector.current * motion.movement_speed.current;
+    if matches!(
+        locomotion.current,
+        LocomotionState::Fly | LocomotionState::Eva | LocomotionState::Climb
+    ) {
+        // The jetpack or the climb owns the velocity. Keep the walking interpolation in step with
+        // it so control hands back smoothly on landing.
+        let tangential = linear_velocity.0 - up * radial_speed;
+        motion.linear_velocity_interp.current = tangential;
+        motion.linear_velocity_interp.target = tangential;
+        motion.moving = motion.movement_vector.current.length() >= 0.01;
+        return;
+    }
+
+    if locomotion.current == LocomotionState::Slide {
+        // Too steep to stand: accelerate downhill, and let input steer across or
+        // down the face but never up it.
+        let slide = tangent_downhill(slope.normal, up)
+            * player_config.slope_slide_acceleration
+            * slope.angle.sin()
+            * time.delta_secs();
+        let steer = without_uphill(motion.movement_vector.current, slope.normal, up)
+            * motion.movement_speed.current
+            * SLIDE_STEER_FACTOR
+            * time.delta_secs();
+        let target = motion.linear_velocity_interp.target + slide + steer;
+        motion.linear_velocity_interp.target = without_uphill(target, slope.normal, up)
+            .clamp_length_max(player_config.max_slide_speed);
+    } else if stance.current == StanceType::Standing {
+        let speed_factor = slope_speed_factor(
+            slope.normal,
+            up,
+            motion.movement_vector.current,
+            &player_config,
+        );
+        let target =
+            motion.movement_vector.current * motion.movement_speed.current * speed_factor;
         motion.linear_velocity_interp.target = target;
     } else {
         const PI: f32 = 3.1459;
@@ -145,10 +198,11 @@ pub fn player_motion_system(
     );
 
     // Strip any radial drift from the interpolated (tangential) velocity, then
-    // recombine with the preserved radial speed.
+    // recombine with the preserved radial speed and whatever the ground is doing.
     let tangential = motion.linear_velocity_interp.current
         - up * motion.linear_velocity_interp.current.dot(up);
-    linear_velocity.0 = up * radial_speed + tangential;
+    let carried = ground.velocity - up * ground.velocity.dot(up);
+    linear_velocity.0 = up * radial_speed + tangential + carried;
 
     trace!(
         "Interpolated Linear Velocity: {{ current {} -> target {} }}",
@@ -163,12 +217,38 @@ pub fn player_motion_system(
 }
 
 pub fn player_rotation_system(
-    mut player_query: Query<&mut Transform, With<Player>>,
+    mut player_query: Query<(&mut Transform, &Locomotion), With<Player>>,
     keys: Res<ButtonInput<KeyCode>>,
     input: Res<Input>,
+    bindings: Res<Bindings>,
     gravity: Res<PlanetGravity>,
+    config: Res<PlayerControlConfig>,
+    time: Res<Time>,
 ) {
-    for mut player_transform in player_query.iter_mut() {
+    for (mut player_transform, locomotion) in player_query.iter_mut() {
+        if locomotion.current == LocomotionState::Eva {
+            // No "down" out here: mouse look yaws and pitches the body about its own
+            // axes and the roll keys roll it.
+            let roll = (keys.pressed(bindings.eva_roll_left) as i32
+                - keys.pressed(bindings.eva_roll_right) as i32) as f32
+                * config.eva_roll_speed
+                * time.delta_secs();
+            let (yaw, pitch) = if keys.pressed(KeyCode::AltLeft) {
+                (0.0, 0.0)
+            } else {
+                (
+                    -input.focus_delta_raw.x.to_radians(),
+                    -input.focus_delta_raw.y.to_radians(),
+                )
+            };
+            player_transform.rotation = (player_transform.rotation
+                * Quat::from_rotation_y(yaw)
+                * Quat::from_rotation_x(pitch)
+                * Quat::from_rotation_z(roll))
+            .normalize();
+            continue;
+        }
+
         // The capsule's up is radial; its facing is a heading on the tangent plane.
         let up = up_at(player_transform.translation, gravity.center);
 
@@ -189,7 +269,15 @@ pub fn player_rotation_system(
         }
 
         // Build a rotation whose local up is `up` and whose -Z is `forward`.
-        player_transform.rotation = Transform::IDENTITY.looking_to(forward, up).rotation;
+        let aligned = Transform::IDENTITY.looking_to(forward, up).rotation;
+        if player_transform.up().dot(up) < EVA_REALIGN_THRESHOLD {
+            // Coming back from EVA at an odd angle: swing upright instead of snapping.
+            player_transform.rotation = player_transform
+                .rotation
+                .slerp(aligned, 1.0 - (-EVA_REALIGN_RATE * time.delta_secs()).exp());
+        } else {
+            player_transform.rotation = aligned;
+        }
     }
 }
 
@@ -237,7 +325,9 @@ pub fn run_move_and_slide(
             &mut TouchedEntities,
             &Collider,
             &Stance,
+            &Locomotion,
             &Motion,
+            &GroundMotion,
         ),
         With<Player>,
     >,
@@ -246,7 +336,18 @@ pub fn run_move_and_slide(
     time: Res<Time>,
     mut gizmos: Gizmos,
 ) {
-    for (entity, mut transform, mut lin_vel, mut touched, collider, stance, motion) in &mut query {
+    for (
+        entity,
+        mut transform,
+        mut lin_vel,
+        mut touched,
+        collider,
+        stance,
+        locomotion,
+        motion,
+        ground,
+    ) in &mut query
+    {
         touched.clear();
         // Perform move and slide
         let MoveAndSlideOutput {
@@ -259,7 +360,7 @@ pub fn run_move_and_slide(
             lin_vel.0,
             time.delta(),
             &MoveAndSlideConfig::default(),
-            &SpatialQueryFilter::from_excluded_entities([entity]),
+            &player_query_filter(entity),
             |hit| {
                 // For each collision, draw debug gizmos
                 if hit.intersects() {
@@ -296,15 +397,18 @@ pub fn run_move_and_slide(
         // the projected velocity so the character comes to rest on slopes. The
         // vertical component is kept so the ride spring still settles the body to
         // its ride height. When the player IS moving we leave the projection
-        // intact so they travel along the slope surface as intended.
-        let grounded: bool =
-            matches!(stance.current, StanceType::Standing | StanceType::Landing);
+        // intact so they travel along the slope surface as intended. A slide is
+        // exempt: it is supposed to carry the player downhill.
+        let grounded: bool = matches!(stance.current, StanceType::Standing | StanceType::Landing)
+            && locomotion.current != LocomotionState::Slide;
         let up = up_at(transform.translation, gravity.center);
         let mut resolved_velocity = projected_velocity;
         if grounded && !motion.moving {
             // Keep only the radial component so the ride spring still settles the
             // body; drop the tangential downhill creep the surface projection adds.
-            resolved_velocity = up * resolved_velocity.dot(up);
+            // Standing on a moving body, keep moving with it.
+            resolved_velocity =
+                up * resolved_velocity.dot(up) + (ground.velocity - up * ground.velocity.dot(up));
         }
         lin_vel.0 = resolved_velocity;
     }
@@ -0,0 +1,147 @@
+//! Riding moving bodies.
+//!
+//! `player_motion_system` rewrites the player's tangential velocity from input every
+//! tick, which on its own leaves the player behind on anything that moves. While
+//! standing, [`update_ground_motion`] samples the velocity of the body under the
+//! ground probe at the player's position (linear plus the spin of the body about
+//! its origin) into [`GroundMotion`]; the motion system adds it on top of the
+//! player's own movement, and the player turns with the body's spin about `up`.
+//!
+//! On leaving the body its last velocity is folded into the player's own, so
+//! momentum carries into the air: the tangential part into the player's movement,
+//! and the radial part (a lift or a bouncing raft) into their radial speed. A jump
+//! takes off from the body's radial speed (see `detect_action_jumping`), so it
+//! already carries that part. Landing on a body likewise measures the fall damage
+//! impact relative to it (see [`ground_body`]).
+
+use avian3d::prelude::{AngularVelocity, ColliderOf, LinearVelocity, ShapeHits};
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{With, Without},
+        system::{Query, Res},
+    },
+    math::{Quat, Vec3},
+    time::Time,
+    transform::components::{GlobalTransform, Transform},
+};
+
+use crate::{
+    Player,
+    body::IgnoreRayCollision,
+    gravity::{PlanetGravity, up_at},
+    locomotion::{Locomotion, LocomotionState},
+    motion::Motion,
+    stance::{Stance, StanceType},
+};
+
+/// The velocity of whatever the player is standing on.
+#[derive(Component, Default)]
+pub struct GroundMotion {
+    /// The rigid body being stood on, if it is one.
+    pub entity: Option<Entity>,
+    /// Velocity of the body at the player's position.
+    pub velocity: Vec3,
+    /// The body's angular velocity.
+    pub angular: Vec3,
+}
+
+/// Sample the velocity of the body under the player, and hand it over to the
+/// player's own velocity when they leave it.
+pub fn update_ground_motion(
+    mut query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &ShapeHits,
+            &Stance,
+            &mut GroundMotion,
+            &mut Motion,
+            &mut LinearVelocity,
+            &Locomotion,
+        ),
+        With<Player>,
+    >,
+    colliders: Query<&ColliderOf>,
+    bodies: Query<(&LinearVelocity, &AngularVelocity, &GlobalTransform), Without<Player>>,
+    ignored_entities: Query<Entity, With<IgnoreRayCollision>>,
+    gravity: Res<PlanetGravity>,
+    time: Res<Time>,
+) {
+    for (
+        entity,
+        mut transform,
+        shape_hits,
+        stance,
+        mut ground,
+        mut motion,
+        mut linear_velocity,
+        locomotion,
+    ) in &mut query
+    {
+        let up = up_at(transform.translation, gravity.center);
+        let grounded = matches!(stance.current, StanceType::Standing | StanceType::Landing);
+        let body = grounded
+            .then(|| {
+                ground_body(
+                    entity,
+                    transform.translation,
+                    shape_hits,
+                    &ignored_entities,
+                    &colliders,
+                    &bodies,
+                )
+            })
+            .flatten();
+
+        let Some((body, velocity, angular)) = body else {
+            if ground.entity.take().is_some() {
+                // Left the body: keep moving with it.
+                let radial = ground.velocity.dot(up);
+                let carried = ground.velocity - up * radial;
+                motion.linear_velocity_interp.current += carried;
+                motion.linear_velocity_interp.target += carried;
+                if locomotion.current != LocomotionState::Jump {
+                    // Walked off: leave at the body's radial speed rather than
+                    // whatever the ride spring had caught up to.
+                    linear_velocity.0 += up * (radial - linear_velocity.0.dot(up));
+                }
+            }
+            ground.velocity = Vec3::ZERO;
+            ground.angular = Vec3::ZERO;
+            continue;
+        };
+
+        ground.entity = Some(body);
+        ground.velocity = velocity;
+        ground.angular = angular;
+
+        // Turn with the body's spin about `up`; the rest of its spin only moves the
+        // point under the player, which `velocity` already covers.
+        let yaw = angular.dot(up) * time.delta_secs();
+        if yaw != 0.0 {
+            transform.rotation = Quat::from_axis_angle(up, yaw) * transform.rotation;
+        }
+    }
+}
+
+/// The rigid body the ground probe of `entity` rests on, if it is one: the body,
+/// its velocity at `point` (linear plus its spin about its origin) and its angular
+/// velocity.
+pub fn ground_body(
+    entity: Entity,
+    point: Vec3,
+    shape_hits: &ShapeHits,
+    ignored_entities: &Query<Entity, With<IgnoreRayCollision>>,
+    colliders: &Query<&ColliderOf>,
+    bodies: &Query<(&LinearVelocity, &AngularVelocity, &GlobalTransform), Without<Player>>,
+) -> Option<(Entity, Vec3, Vec3)> {
+    let hit = shape_hits
+        .iter_sorted()
+        .find(|hit| hit.entity != entity && !ignored_entities.contains(hit.entity))?;
+    let body = colliders.get(hit.entity).map_or(hit.entity, |c| c.body);
+    let (linear, angular, transform) = bodies.get(body).ok()?;
+    let offset = point - transform.translation();
+    Some((body, linear.0 + angular.0.cross(offset), angular.0))
+}
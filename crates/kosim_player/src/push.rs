@@ -0,0 +1,105 @@
+//! Pushing and being pushed by dynamic bodies.
+//!
+//! The player's velocity is driven by input rather than by the solver, so contacts
+//! with props are resolved here instead. For every dynamic body the player touched
+//! while moving ([`TouchedEntities`]) or is in contact with ([`CollidingEntities`]),
+//! the speed at which the two close along the tangent plane decides who pushes:
+//!
+//! - the player walking into a body gives it an impulse of
+//!   [`PlayerControlConfig::push_strength`] × closing speed × the reduced mass, so
+//!   light props get shoved along and heavy ones barely budge;
+//! - a body moving into the player shoves the player by
+//!   [`PlayerControlConfig::push_reaction`] × closing speed, scaled by the body's
+//!   share of the combined mass.
+
+use avian3d::prelude::{
+    ColliderOf, CollidingEntities, ComputedMass, LinearVelocity, Mass, RigidBody,
+};
+use bevy::{
+    ecs::{
+        entity::Entity,
+        query::{With, Without},
+        system::{Query, Res},
+    },
+    transform::components::{GlobalTransform, Transform},
+};
+
+use crate::{
+    Player,
+    carry::Carried,
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+    motion::{Motion, TouchedEntities},
+    platform::GroundMotion,
+};
+
+/// Trade pushes between the player and the dynamic bodies it is in contact with.
+pub fn push_touched_bodies(
+    mut player_query: Query<
+        (
+            &Transform,
+            &mut LinearVelocity,
+            &Mass,
+            &TouchedEntities,
+            &CollidingEntities,
+            &GroundMotion,
+            &mut Motion,
+        ),
+        With<Player>,
+    >,
+    mut bodies: Query<
+        (
+            &RigidBody,
+            &ComputedMass,
+            &GlobalTransform,
+            &mut LinearVelocity,
+        ),
+        (Without<Player>, Without<Carried>),
+    >,
+    colliders: Query<&ColliderOf>,
+    config: Res<PlayerControlConfig>,
+    gravity: Res<PlanetGravity>,
+) {
+    for (transform, mut linear_velocity, mass, touched, colliding, ground, mut motion) in
+        &mut player_query
+    {
+        let up = up_at(transform.translation, gravity.center);
+        let mut seen: Vec<Entity> = Vec::new();
+        for &hit in touched.iter().chain(colliding.iter()) {
+            let body = colliders.get(hit).map_or(hit, |c| c.body);
+            // The body underfoot carries the player instead (see `crate::platform`).
+            if seen.contains(&body) || ground.entity == Some(body) {
+                continue;
+            }
+            seen.push(body);
+            let Ok((rigid_body, body_mass, body_transform, mut body_velocity)) =
+                bodies.get_mut(body)
+            else {
+                continue;
+            };
+            if !rigid_body.is_dynamic() || body_mass.value() <= 0.0 {
+                continue;
+            }
+
+            let offset = body_transform.translation() - transform.translation;
+            let Some(dir) = (offset - up * offset.dot(up)).try_normalize() else {
+                continue;
+            };
+            let player_mass = mass.0;
+            let body_mass = body_mass.value();
+            let total_mass = player_mass + body_mass;
+            let closing_speed = (linear_velocity.0 - body_velocity.0).dot(dir);
+
+            if closing_speed > 0.0 {
+                let reduced_mass = player_mass * body_mass / total_mass;
+                let impulse = config.push_strength * closing_speed * reduced_mass;
+                body_velocity.0 += dir * impulse / body_mass;
+            } else if closing_speed < 0.0 {
+                // `closing_speed` is negative here, so this points away from the body.
+                let shove = dir * closing_speed * config.push_reaction * body_mass / total_mass;
+                linear_velocity.0 += shove;
+                motion.linear_velocity_interp.current += shove;
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Slope limits. The ride spring floats the player at a fixed height whatever the
+//! ground is shaped like, so without this the player could walk straight up any
+//! surface the ground probe can see.
+//!
+//! [`update_ground_slope`] reads the ground normal — from the voxel field on terrain,
+//! from the probe's [`ShapeHits`] on any other body — and measures it against the
+//! radial `up`. Ground steeper than
+//! [`PlayerControlConfig::max_walkable_slope_angle`] puts the player in
+//! [`LocomotionState::Slide`](crate::locomotion::LocomotionState::Slide): they
+//! accelerate downhill and cannot push uphill. Walkable slopes only slow the player
+//! down when heading uphill (see [`slope_speed_factor`]).
+
+use avian3d::prelude::ShapeHits;
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        system::{Query, Res},
+    },
+    math::Vec3,
+    transform::components::Transform,
+};
+
+use kosim_world::{ChunkManager, TerrainChunk};
+
+use crate::{
+    Player,
+    body::{IgnoreRayCollision, VoxelGround},
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+};
+
+/// Radius, in voxels, of the voxel-field sample that smooths the terrain normal:
+/// about the player's footprint.
+const GROUND_NORMAL_RADIUS: i32 = 2;
+
+/// The ground under the player as seen by the ground probe.
+#[derive(Component)]
+pub struct GroundSlope {
+    /// Surface normal of the ground; `up` when the probe hit nothing.
+    pub normal: Vec3,
+    /// Angle between `normal` and `up`, in radians.
+    pub angle: f32,
+}
+
+impl Default for GroundSlope {
+    fn default() -> Self {
+        Self {
+            normal: Vec3::Y,
+            angle: 0.0,
+        }
+    }
+}
+
+impl GroundSlope {
+    /// Whether the slope is shallow enough to stand and walk on.
+    pub fn is_walkable(&self, config: &PlayerControlConfig) -> bool {
+        self.angle <= config.max_walkable_slope_angle.to_radians()
+    }
+}
+
+/// Angle in radians between a ground `normal` and `up`.
+pub fn slope_angle(normal: Vec3, up: Vec3) -> f32 {
+    normal.dot(up).clamp(-1.0, 1.0).acos()
+}
+
+/// The direction straight down the slope along the surface, or zero on flat ground.
+pub fn downhill(normal: Vec3, up: Vec3) -> Vec3 {
+    let down = -up;
+    (down - normal * down.dot(normal)).normalize_or_zero()
+}
+
+/// [`downhill`] flattened onto the tangent plane of the planet, which is the plane
+/// the player's movement velocity lives in.
+pub fn tangent_downhill(normal: Vec3, up: Vec3) -> Vec3 {
+    let downhill = downhill(normal, up);
+    (downhill - up * downhill.dot(up)).normalize_or_zero()
+}
+
+/// Remove the uphill part of a tangential `velocity`, leaving sideways and downhill
+/// motion untouched.
+pub fn without_uphill(velocity: Vec3, normal: Vec3, up: Vec3) -> Vec3 {
+    let uphill = -tangent_downhill(normal, up);
+    let climb = velocity.dot(uphill);
+    if climb > 0.0 {
+        velocity - uphill * climb
+    } else {
+        velocity
+    }
+}
+
+/// Movement speed multiplier for heading in `direction` on walkable ground: 1 on
+/// the flat, across, or downhill, easing to
+/// [`PlayerControlConfig::uphill_speed_factor`] going straight up the steepest
+/// walkable slope.
+pub fn slope_speed_factor(
+    normal: Vec3,
+    up: Vec3,
+    direction: Vec3,
+    config: &PlayerControlConfig,
+) -> f32 {
+    let max_angle = config.max_walkable_slope_angle.to_radians();
+    let steepness = (slope_angle(normal, up) / max_angle).clamp(0.0, 1.0);
+    let climb = direction
+        .normalize_or_zero()
+        .dot(-tangent_downhill(normal, up))
+        .max(0.0);
+    1.0 - climb * steepness * (1.0 - config.uphill_speed_factor)
+}
+
+/// Read the ground normal under the player. Terrain colliders are voxel boxes whose
+/// faces are all axis-aligned, so on terrain the normal comes from the voxel field
+/// around the foot of the [`VoxelGround`] probe instead of from the collider.
+pub fn update_ground_slope(
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &ShapeHits,
+            &VoxelGround,
+            &mut GroundSlope,
+        ),
+        With<Player>,
+    >,
+    ignored_entities: Query<Entity, With<IgnoreRayCollision>>,
+    terrain: Query<(), With<TerrainChunk>>,
+    chunks: Option<Res<ChunkManager>>,
+    gravity: Res<PlanetGravity>,
+) {
+    for (entity, transform, shape_hits, voxel_ground, mut slope) in &mut query {
+        let up = up_at(transform.translation, gravity.center);
+        let hit = shape_hits
+            .iter_sorted()
+            .find(|hit| hit.entity != entity && !ignored_entities.contains(hit.entity));
+        slope.normal = match hit {
+            // Standing on a body (a platform, a crate): its collider has the true
+            // surface. `normal1` is the probe sphere's own normal at the contact,
+            // pointing into the ground; flipped, it is the ground's normal.
+            Some(hit) if !terrain.contains(hit.entity) => (-hit.normal1).normalize_or(up),
+            _ => chunks
+                .as_ref()
+                .filter(|_| voxel_ground.distance.is_finite())
+                .and_then(|chunks| {
+                    let foot = transform.translation - up * voxel_ground.distance;
+                    chunks.world().surface_normal(foot, GROUND_NORMAL_RADIUS)
+                })
+                .unwrap_or(up),
+        };
+        slope.angle = slope_angle(slope.normal, up);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    /// Ground normal tilted `degrees` from `Vec3::Y`, rising toward +X.
+    fn tilted(degrees: f32) -> Vec3 {
+        let angle = degrees.to_radians();
+        Vec3::new(-angle.sin(), angle.cos(), 0.0)
+    }
+
+    fn slope(degrees: f32) -> GroundSlope {
+        let normal = tilted(degrees);
+        GroundSlope {
+            normal,
+            angle: slope_angle(normal, Vec3::Y),
+        }
+    }
+
+    #[test]
+    fn slope_angle_measures_from_up() {
+        assert!(slope_angle(Vec3::Y, Vec3::Y).abs() < EPSILON);
+        assert!((slope_angle(tilted(30.0), Vec3::Y) - 30f32.to_radians()).abs() < EPSILON);
+        assert!((slope_angle(Vec3::X, Vec3::Y) - 90f32.to_radians()).abs() < EPSILON);
+        // Up need not be world Y: on the side of the planet a wall can be flat ground.
+        assert!(slope_angle(Vec3::X, Vec3::X).abs() < EPSILON);
+    }
+
+    #[test]
+    fn walkable_up_to_the_configured_angle() {
+        let config = PlayerControlConfig::default();
+        let max = config.max_walkable_slope_angle;
+        assert!(slope(0.0).is_walkable(&config));
+        assert!(slope(max - 1.0).is_walkable(&config));
+        assert!(!slope(max + 1.0).is_walkable(&config));
+        assert!(!slope(90.0).is_walkable(&config));
+    }
+
+    #[test]
+    fn tangent_downhill_points_down_the_slope_in_the_tangent_plane() {
+        // Rising toward +X, so downhill is -X.
+        let down = tangent_downhill(tilted(30.0), Vec3::Y);
+        assert!(down.distance(Vec3::NEG_X) < EPSILON);
+        assert_eq!(tangent_downhill(Vec3::Y, Vec3::Y), Vec3::ZERO);
+    }
+
+    #[test]
+    fn without_uphill_keeps_downhill_and_sideways_motion() {
+        let normal = tilted(30.0);
+        let velocity = Vec3::new(3.0, 0.0, 2.0);
+        assert!(
+            without_uphill(velocity, normal, Vec3::Y).distance(Vec3::new(0.0, 0.0, 2.0)) < EPSILON
+        );
+        let downhill = Vec3::new(-3.0, 0.0, 2.0);
+        assert_eq!(without_uphill(downhill, normal, Vec3::Y), downhill);
+        assert_eq!(without_uphill(velocity, Vec3::Y, Vec3::Y), velocity);
+    }
+
+    #[test]
+    fn slope_speed_factor_only_slows_uphill() {
+        let config = PlayerControlConfig::default();
+        let steepest = tilted(config.max_walkable_slope_angle);
+        assert!((slope_speed_factor(Vec3::Y, Vec3::Y, Vec3::X, &config) - 1.0).abs() < EPSILON);
+        assert!(
+            (slope_speed_factor(steepest, Vec3::Y, Vec3::NEG_X, &config) - 1.0).abs() < EPSILON
+        );
+        assert!((slope_speed_factor(steepest, Vec3::Y, Vec3::Z, &config) - 1.0).abs() < EPSILON);
+        assert!(
+            (slope_speed_factor(steepest, Vec3::Y, Vec3::X, &config) - config.uphill_speed_factor)
+                .abs()
+                < EPSILON
+        );
+        // Half as steep, half the slowdown.
+        let half = tilted(config.max_walkable_slope_angle * 0.5);
+        let expected = 1.0 - 0.5 * (1.0 - config.uphill_speed_factor);
+        assert!((slope_speed_factor(half, Vec3::Y, Vec3::X, &config) - expected).abs() < EPSILON);
+    }
+}
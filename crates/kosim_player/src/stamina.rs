@@ -0,0 +1,145 @@
+//! Stamina: drained by sprinting and jumping, regenerated after a short delay.
+//!
+//! Running out leaves the player [`Stamina::exhausted`]: sprinting is refused until
+//! stamina recovers to [`PlayerControlConfig::stamina_recover_fraction`]. Below
+//! [`PlayerControlConfig::stamina_low_fraction`] footsteps get heavier and the
+//! player starts breathing hard, both scaled by [`Stamina::fatigue`].
+
+use bevy::{
+    asset::{AssetServer, Handle},
+    ecs::{
+        component::Component,
+        query::With,
+        resource::Resource,
+        system::{Commands, Query, Res, ResMut},
+    },
+    log::info,
+    time::Time,
+};
+use bevy_kira_audio::{Audio, AudioControl, AudioSource};
+use bevy_turborand::{DelegatedRng, GlobalRng};
+
+use crate::{Player, config::PlayerControlConfig, locomotion::Locomotion, motion::Motion};
+
+const BREATHING_SFX_PATH: &str = "audio/breathing.wav";
+/// Seconds between breaths when just below the low threshold, and when empty.
+const BREATH_INTERVAL_CALM: f32 = 1.6;
+const BREATH_INTERVAL_WINDED: f32 = 0.7;
+/// Breath volume (dB) when just below the low threshold, and when empty.
+const BREATH_VOLUME_CALM: f32 = -24.0;
+const BREATH_VOLUME_WINDED: f32 = -10.0;
+
+#[derive(Component)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// Seconds until regeneration starts again after the last drain.
+    pub regen_delay: f32,
+    /// Ran dry; stays set until stamina recovers past the recover fraction.
+    pub exhausted: bool,
+}
+
+impl Stamina {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_delay: 0.0,
+            exhausted: false,
+        }
+    }
+
+    /// Take `amount` and hold off regeneration. Returns `false` (taking nothing)
+    /// when there is not enough stamina.
+    pub fn try_spend(&mut self, amount: f32, config: &PlayerControlConfig) -> bool {
+        if self.exhausted || self.current < amount {
+            return false;
+        }
+        self.drain(amount, config);
+        true
+    }
+
+    /// Take up to `amount` and hold off regeneration.
+    pub fn drain(&mut self, amount: f32, config: &PlayerControlConfig) {
+        self.current = (self.current - amount).max(0.0);
+        self.regen_delay = config.stamina_regen_delay;
+        if self.current <= 0.0 && !self.exhausted {
+            self.exhausted = true;
+            info!("Stamina: EXHAUSTED");
+        }
+    }
+
+    /// 0 at or above the low threshold, rising to 1 when empty.
+    pub fn fatigue(&self, config: &PlayerControlConfig) -> f32 {
+        let low = self.max * config.stamina_low_fraction;
+        if low <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - self.current / low).clamp(0.0, 1.0)
+    }
+}
+
+/// Drain stamina while sprinting, and regenerate it once the delay has passed.
+pub fn update_stamina(
+    mut query: Query<(&mut Stamina, &Motion, &Locomotion), With<Player>>,
+    config: Res<PlayerControlConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for (mut stamina, motion, locomotion) in &mut query {
+        if motion.sprinting && motion.moving && locomotion.current.is_grounded() {
+            stamina.drain(config.sprint_stamina_drain * dt, &config);
+        } else if stamina.regen_delay > 0.0 {
+            stamina.regen_delay = (stamina.regen_delay - dt).max(0.0);
+        } else {
+            stamina.current = (stamina.current + config.stamina_regen_rate * dt).min(stamina.max);
+        }
+
+        if stamina.exhausted && stamina.current >= stamina.max * config.stamina_recover_fraction {
+            stamina.exhausted = false;
+            info!("Stamina: RECOVERED");
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct BreathingAudioHandle(Handle<AudioSource>);
+
+pub fn load_breathing_sfx(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BreathingAudioHandle(asset_server.load(BREATHING_SFX_PATH)));
+}
+
+/// Time until the next breath sound.
+#[derive(Component, Default)]
+pub struct Breathing {
+    pub timer: f32,
+}
+
+/// Play breaths, faster and louder the more fatigued the player is.
+pub fn play_breathing_sfx(
+    mut query: Query<(&Stamina, &mut Breathing), With<Player>>,
+    config: Res<PlayerControlConfig>,
+    mut global_rng: ResMut<GlobalRng>,
+    audio: Res<Audio>,
+    handle: Res<BreathingAudioHandle>,
+    time: Res<Time>,
+) {
+    for (stamina, mut breathing) in &mut query {
+        let fatigue = stamina.fatigue(&config);
+        if fatigue <= 0.0 {
+            breathing.timer = 0.0;
+            continue;
+        }
+        breathing.timer -= time.delta_secs();
+        if breathing.timer > 0.0 {
+            continue;
+        }
+        breathing.timer =
+            BREATH_INTERVAL_CALM + (BREATH_INTERVAL_WINDED - BREATH_INTERVAL_CALM) * fatigue;
+        let volume = BREATH_VOLUME_CALM + (BREATH_VOLUME_WINDED - BREATH_VOLUME_CALM) * fatigue;
+        audio
+            .play(handle.0.clone())
+            .with_playback_rate(global_rng.f64() * 0.1 + 0.95)
+            .with_volume(volume);
+    }
+}
@@ -1,19 +1,25 @@
-use avian3d::prelude::ShapeHits;
+use avian3d::prelude::{AngularVelocity, ColliderOf, LinearVelocity, ShapeHits};
 use bevy::{
     ecs::{
         component::Component,
         entity::Entity,
         message::MessageWriter,
-        query::With,
+        query::{With, Without},
         system::{Query, Res},
     },
-    log::{info, trace, warn},
-    time::Time,
+    log::{info, warn},
+    math::Vec3,
+    transform::components::{GlobalTransform, Transform},
 };
-use kosim_utility::format_value::format_value_f32;
 
 use crate::{
-    Player, action::{DEFAULT_STEP_VOLUME, FootstepDirection, FootstepEvent}, body::{IgnoreRayCollision, StandingSpringForce, compute_ray_length}, config::PlayerControlConfig
+    Player,
+    body::{IgnoreRayCollision, StandingSpringForce, VoxelGround, compute_ray_length},
+    config::PlayerControlConfig,
+    gravity::{PlanetGravity, up_at},
+    health::{DamageEvent, DamageSource, fall_damage},
+    locomotion::Locomotion,
+    platform::ground_body,
 };
 
 #[derive(Debug, PartialEq, Clone)]
@@ -23,48 +29,37 @@ pub enum StanceType {
     Landing,
 }
 
+/// Ground contact as read from the ride-spring probe. The player's overall state
+/// (and its lockouts) lives in [`Locomotion`], which is derived from this.
 #[derive(Component)]
 pub struct Stance {
-    pub lockout_timer: f32,
     pub current: StanceType,
     pub crouched: bool,
-}
-
-pub trait ForceSet {
-    fn force_set(&mut self, next_val: StanceType);
-}
-
-impl ForceSet for Stance {
-    fn force_set(&mut self, next_val: StanceType) {
-        self.current = next_val;
-    }
-}
-
-pub trait SetWithLockout {
-    fn try_set(&mut self, next_val: StanceType, lockout: Option<f32>) -> Result<(), String>;
-}
-
-impl SetWithLockout for Stance {
-    fn try_set(&mut self, next_val: StanceType, lockout: Option<f32>) -> Result<(), String> {
-        if self.lockout_timer <= 0.0 {
-            self.current = next_val;
-            self.lockout_timer = lockout.unwrap_or(0.0f32);
-            Ok(())
-        } else {
-            Err(format!(
-                "Cannot change stance to {:?} because lockout is still active for {} seconds.",
-                next_val, self.lockout_timer
-            ))
-        }
-    }
+    /// What the crouch input asks for. Differs from `crouched` while there is no
+    /// headroom to stand up.
+    pub crouch_requested: bool,
 }
 
 pub fn compute_next_stance(
-    mut query: Query<(Entity, &StandingSpringForce, &mut Stance, &ShapeHits), With<Player>>,
+    mut query: Query<
+        (
+            Entity,
+            &StandingSpringForce,
+            &mut Stance,
+            &Locomotion,
+            &ShapeHits,
+            &VoxelGround,
+            &Transform,
+            &LinearVelocity,
+        ),
+        With<Player>,
+    >,
     ignored_entities: Query<Entity, With<IgnoreRayCollision>>,
-    mut ev_footstep: MessageWriter<FootstepEvent>,
+    colliders: Query<&ColliderOf>,
+    bodies: Query<(&LinearVelocity, &AngularVelocity, &GlobalTransform), Without<Player>>,
     config: Res<PlayerControlConfig>,
-    time: Res<Time>,
+    gravity: Res<PlanetGravity>,
+    mut ev_damage: MessageWriter<DamageEvent>,
 ) {
     if query.is_empty() || query.iter().len() > 1 {
         warn!(
@@ -73,15 +68,25 @@ pub fn compute_next_stance(
         );
     }
 
-    for (entity, standing_spring_height, mut stance, ray_hits) in &mut query {
+    for (
+        entity,
+        standing_spring_height,
+        mut stance,
+        locomotion,
+        ray_hits,
+        voxel_ground,
+        transform,
+        linear_velocity,
+    ) in &mut query
+    {
         // Compute the next stance for the player.
         let previous_stance: StanceType = stance.current.clone();
         let mut next_stance: StanceType = stance.current.clone();
 
-        let ray_length: f32 = compute_ray_length(entity, ignored_entities, ray_hits);
+        let ray_length: f32 = compute_ray_length(entity, ignored_entities, ray_hits, voxel_ground);
 
         // If your locked in you cannot change state.
-        if stance.lockout_timer <= 0.0 {
+        if !locomotion.is_locked_out() {
             let ride_height: f32 = standing_spring_height.length.current;
             // Top of the ride band: within this the ride spring is engaged and the
             // body is grounded. Add hysteresis on top so a body already grounded
@@ -103,6 +108,30 @@ pub fn compute_next_stance(
                 // touchdown transient that fires the landing sound below and then
                 // settles to Standing next frame.
                 next_stance = StanceType::Landing;
+
+                // Touchdown is where a hard fall hurts: damage from the speed toward
+                // the planet at the moment of contact, relative to what is landed on
+                // so a lift rising to meet the player hurts and one sinking away
+                // does not.
+                let up = up_at(transform.translation, gravity.center);
+                let ground_velocity = ground_body(
+                    entity,
+                    transform.translation,
+                    ray_hits,
+                    &ignored_entities,
+                    &colliders,
+                    &bodies,
+                )
+                .map_or(Vec3::ZERO, |(_, velocity, _)| velocity);
+                let impact_speed = -(linear_velocity.0 - ground_velocity).dot(up);
+                let damage = fall_damage(impact_speed, &config);
+                if damage > 0.0 {
+                    ev_damage.write(DamageEvent {
+                        target: entity,
+                        amount: damage,
+                        source: DamageSource::Fall { impact_speed },
+                    });
+                }
             } else {
                 // Within the grounded band and already grounded (Standing, or the
                 // one-frame Landing transient): settle to Standing. Requiring
@@ -112,17 +141,6 @@ pub fn compute_next_stance(
                 // airborne movement and felt stuck in the air.
                 next_stance = StanceType::Standing;
             }
-        } else if stance.lockout_timer != 0.0 {
-            stance.lockout_timer -= time.delta_secs();
-            stance.lockout_timer = f32::max(stance.lockout_timer, 0.0);
-            if stance.lockout_timer <= 0.0 {
-                trace!("Stance lockout: RELEASED");
-            } else {
-                trace!(
-                    "Stance lockout: {}",
-                    format_value_f32(stance.lockout_timer, Some(2), false)
-                );
-            }
         }
 
         if next_stance != previous_stance {
@@ -132,21 +150,6 @@ pub fn compute_next_stance(
             );
         }
 
-        // handle footstep sound event when the state has changed and only then.
-        if next_stance != stance.current {
-            match next_stance {
-                StanceType::Landing => {
-                    // This is the sound effect that plays when the player has jumped or fallen and will land with both feet on the ground.
-                    // this effect will play centered and will not pan in any direction.
-                    ev_footstep.write(FootstepEvent {
-                        dir: FootstepDirection::None,
-                        volume: DEFAULT_STEP_VOLUME,
-                    });
-                }
-                _ => (),
-            }
-        }
-
         // Update the current stance.
         stance.current = next_stance.clone();
     }
@@ -4,6 +4,7 @@ use std::ops::{Add, Mul, Sub};
 pub mod mesh;
 pub mod format_value;
 pub mod interpolated_value;
+pub mod toml_file;
 
 #[macro_export]
 macro_rules! ternary {
@@ -0,0 +1,121 @@
+//! Data files kept as TOML: configs, definitions and saves.
+//!
+//! A type implements [`TomlFile`] with a `validate` that lists what is wrong with
+//! a parsed value, and gets parsing, saving and loading that reject an invalid one
+//! with a [`TomlFileError`]. Registering a [`TomlFileLoader`] for it lets the asset
+//! server load and hot-reload it the same way.
+
+use std::{fmt, io, marker::PhantomData, path::Path};
+
+use bevy::{
+    asset::{Asset, AssetLoader, LoadContext, io::Reader},
+    reflect::TypePath,
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// A value read from and written to a TOML file.
+pub trait TomlFile: DeserializeOwned {
+    /// Every problem that makes a parsed value unusable, described for the log.
+    /// Empty when it is usable.
+    fn validate(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Parse `text`, rejecting a value that does not validate.
+    fn from_toml(text: &str) -> Result<Self, TomlFileError> {
+        let value: Self = toml::from_str(text).map_err(TomlFileError::Parse)?;
+        let problems = value.validate();
+        if !problems.is_empty() {
+            return Err(TomlFileError::Invalid(problems));
+        }
+        Ok(value)
+    }
+
+    fn to_toml(&self) -> Result<String, TomlFileError>
+    where
+        Self: Serialize,
+    {
+        toml::to_string(self).map_err(TomlFileError::Serialize)
+    }
+
+    fn load(file: impl AsRef<Path>) -> Result<Self, TomlFileError> {
+        Self::from_toml(&std::fs::read_to_string(file).map_err(TomlFileError::Io)?)
+    }
+
+    fn save(&self, file: impl AsRef<Path>) -> Result<(), TomlFileError>
+    where
+        Self: Serialize,
+    {
+        std::fs::write(file, self.to_toml()?).map_err(TomlFileError::Io)
+    }
+}
+
+/// Why a [`TomlFile`] could not be read or written.
+#[derive(Debug)]
+pub enum TomlFileError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for TomlFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlFileError::Io(e) => write!(f, "could not access the file: {e}"),
+            TomlFileError::Parse(e) => write!(f, "the file is not valid: {e}"),
+            TomlFileError::Serialize(e) => write!(f, "could not write the file: {e}"),
+            TomlFileError::Invalid(problems) => {
+                write!(f, "the file has invalid values: {}", problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for TomlFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TomlFileError::Io(e) => Some(e),
+            TomlFileError::Parse(e) => Some(e),
+            TomlFileError::Serialize(e) => Some(e),
+            TomlFileError::Invalid(_) => None,
+        }
+    }
+}
+
+/// Loads `.toml` assets as `T`, rejecting values that do not validate. Several of
+/// these can be registered at once; loads are told apart by asset type.
+#[derive(TypePath)]
+pub struct TomlFileLoader<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for TomlFileLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Asset + TomlFile> AssetLoader for TomlFileLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = TomlFileError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(TomlFileError::Io)?;
+        let text = std::str::from_utf8(&bytes)
+            .map_err(|e| TomlFileError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        T::from_toml(text)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+}
@@ -0,0 +1,70 @@
+//! Weather ambience audio. Rain and wind each loop forever on their own audio
+//! channel; the weather only moves the channel volumes, so a change of weather
+//! crossfades the loops at the same rate the visuals blend.
+
+use bevy::prelude::*;
+use bevy_kira_audio::{AudioApp, AudioChannel, AudioControl};
+
+use crate::WeatherState;
+
+/// Quietest volume (dB) a loop is driven to; below this it is effectively silent.
+const SILENT_VOLUME: f32 = -60.0;
+/// Volume (dB) of a loop at full gain, matched to the background music level.
+const FULL_VOLUME: f32 = -14.0;
+
+#[derive(Resource)]
+pub struct RainAmbienceChannel;
+
+#[derive(Resource)]
+pub struct WindAmbienceChannel;
+
+pub struct WeatherAmbiencePlugin;
+
+impl Plugin for WeatherAmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<RainAmbienceChannel>()
+            .add_audio_channel::<WindAmbienceChannel>()
+            .add_systems(Startup, start_weather_ambience);
+    }
+}
+
+fn start_weather_ambience(
+    asset_server: Res<AssetServer>,
+    rain: Res<AudioChannel<RainAmbienceChannel>>,
+    wind: Res<AudioChannel<WindAmbienceChannel>>,
+) {
+    rain.play(asset_server.load("audio/rain-loop.wav"))
+        .with_volume(SILENT_VOLUME)
+        .looped();
+    wind.play(asset_server.load("audio/wind-loop.wav"))
+        .with_volume(SILENT_VOLUME)
+        .looped();
+}
+
+/// Map a linear gain in `[0, 1]` to the channel volume in decibels.
+fn gain_to_volume(gain: f32) -> f32 {
+    if gain <= 0.001 {
+        SILENT_VOLUME
+    } else {
+        (FULL_VOLUME + 20.0 * gain.log10()).max(SILENT_VOLUME)
+    }
+}
+
+pub fn apply_weather_ambience(
+    state: Res<WeatherState>,
+    rain: Res<AudioChannel<RainAmbienceChannel>>,
+    wind: Res<AudioChannel<WindAmbienceChannel>>,
+    mut applied: Local<Option<(f32, f32)>>,
+) {
+    let volumes = (
+        gain_to_volume(state.current.rain_volume),
+        gain_to_volume(state.current.wind_volume),
+    );
+    // Only issue channel commands while the volumes are actually moving.
+    if *applied == Some(volumes) {
+        return;
+    }
+    rain.set_volume(volumes.0);
+    wind.set_volume(volumes.1);
+    *applied = Some(volumes);
+}
@@ -0,0 +1,53 @@
+//! Weather-driven fog. The camera already carries [`VolumetricFog`]; a
+//! [`FogVolume`] parented to the camera gives it something to march through, and
+//! its density follows the current [`WeatherParams`](crate::weather::WeatherParams).
+
+use bevy::light::{FogVolume, VolumetricFog};
+use bevy::prelude::*;
+use kosim_camera::GameCamera;
+
+use crate::WeatherState;
+
+/// Edge length of the fog volume that travels with the camera.
+const FOG_VOLUME_SIZE: f32 = 400.0;
+
+/// Marks the fog volume that follows the camera.
+#[derive(Component)]
+pub struct WeatherFogVolume;
+
+pub fn spawn_weather_fog(mut commands: Commands, camera: Query<Entity, With<GameCamera>>) {
+    let Ok(camera) = camera.single() else {
+        warn!("No GameCamera found, weather fog will not be spawned!");
+        return;
+    };
+    let volume = commands
+        .spawn((
+            Name::new("WeatherFogVolume"),
+            FogVolume {
+                density_factor: 0.0,
+                ..default()
+            },
+            Transform::from_scale(Vec3::splat(FOG_VOLUME_SIZE)),
+            WeatherFogVolume,
+        ))
+        .id();
+    commands.entity(camera).add_children(&[volume]);
+}
+
+pub fn apply_weather_fog(
+    state: Res<WeatherState>,
+    mut volumes: Query<&mut FogVolume, With<WeatherFogVolume>>,
+    mut camera_fog: Query<&mut VolumetricFog, With<GameCamera>>,
+) {
+    let params = state.current;
+    for mut volume in &mut volumes {
+        if volume.density_factor != params.fog_density {
+            volume.density_factor = params.fog_density;
+        }
+    }
+    for mut fog in &mut camera_fog {
+        if fog.ambient_intensity != params.fog_ambient {
+            fog.ambient_intensity = params.fog_ambient;
+        }
+    }
+}
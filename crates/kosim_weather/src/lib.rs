@@ -0,0 +1,197 @@
+//! `kosim_weather` — per-region weather for the planet.
+//!
+//! Each [`weather::WeatherRegion`] of the planet runs its own deterministic
+//! [`weather::WeatherKind`] state machine (see [`weather`]). The region under the
+//! [`GameCamera`] drives the local environment: its [`weather::WeatherParams`] are
+//! crossfaded whenever the kind changes (or the camera crosses into another region)
+//! and then applied to the camera's fog ([`fog`]), the rain particles around the
+//! camera ([`rain`]), the [`Wind`] resource and the ambience loops ([`ambience`]).
+
+use bevy::prelude::*;
+use kosim_camera::GameCamera;
+
+pub mod ambience;
+pub mod fog;
+pub mod rain;
+pub mod weather;
+
+use weather::{RegionWeather, WeatherKind, WeatherParams, WeatherRegion, wind_heading};
+
+/// Tunable parameters for the weather simulation.
+#[derive(Resource, Clone, Debug)]
+pub struct WeatherConfig {
+    /// Seed for every weather roll. The same seed always produces the same weather.
+    pub seed: u32,
+    /// Planet centre in world space. Must match the world's planet centre
+    /// (`kosim_world` centres it on the origin).
+    pub planet_center: Vec3,
+    /// Simulated seconds between state-machine steps.
+    pub cycle_seconds: f32,
+    /// Seconds to crossfade the environment from one kind to the next.
+    pub transition_seconds: f32,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            planet_center: Vec3::ZERO,
+            cycle_seconds: 120.0,
+            transition_seconds: 20.0,
+        }
+    }
+}
+
+/// The weather the camera is currently experiencing, and the crossfade toward it.
+#[derive(Resource)]
+pub struct WeatherState {
+    /// Simulated seconds since the weather started. Drives the epoch, so setting it
+    /// directly jumps the weather to a known point in the sequence.
+    pub clock: f32,
+    /// The region the camera is in, with its cached state machine.
+    pub region: Option<RegionWeather>,
+    /// The kind being blended toward.
+    pub kind: WeatherKind,
+    /// Params at the start of the current crossfade.
+    pub from: WeatherParams,
+    /// Crossfade progress, `0` (at `from`) to `1` (at `kind.params()`).
+    pub blend: f32,
+    /// The blended params consumers should apply this frame.
+    pub current: WeatherParams,
+}
+
+impl Default for WeatherState {
+    fn default() -> Self {
+        let params = WeatherKind::Clear.params();
+        Self {
+            clock: 0.0,
+            region: None,
+            kind: WeatherKind::Clear,
+            from: params,
+            blend: 1.0,
+            current: params,
+        }
+    }
+}
+
+impl WeatherState {
+    /// The state-machine epoch for the current clock.
+    pub fn epoch(&self, config: &WeatherConfig) -> u64 {
+        (self.clock / config.cycle_seconds.max(f32::EPSILON)).floor() as u64
+    }
+}
+
+/// The wind at the camera, tangent to the planet surface.
+#[derive(Resource, Default)]
+pub struct Wind {
+    /// Unit direction the wind blows toward.
+    pub direction: Vec3,
+    /// Speed in units/second.
+    pub speed: f32,
+}
+
+impl Wind {
+    /// Wind velocity in units/second.
+    pub fn velocity(&self) -> Vec3 {
+        self.direction * self.speed
+    }
+}
+
+pub struct KosimWeatherPlugin;
+
+impl Plugin for KosimWeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeatherConfig>()
+            .init_resource::<WeatherState>()
+            .init_resource::<Wind>()
+            .add_plugins(ambience::WeatherAmbiencePlugin)
+            .add_systems(Startup, (fog::spawn_weather_fog, rain::spawn_rain))
+            .add_systems(
+                Update,
+                (
+                    update_weather_state,
+                    update_wind,
+                    fog::apply_weather_fog,
+                    rain::update_rain,
+                    ambience::apply_weather_ambience,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Local "up" at `pos` relative to the planet centre.
+#[inline]
+pub fn up_at(pos: Vec3, center: Vec3) -> Vec3 {
+    (pos - center).normalize_or(Vec3::Y)
+}
+
+/// A stable east/north tangent basis at `up`. East follows lines of latitude and
+/// falls back to world +X at the poles.
+pub fn tangent_basis(up: Vec3) -> (Vec3, Vec3) {
+    let east = Vec3::Y.cross(up).try_normalize().unwrap_or(Vec3::X);
+    let north = up.cross(east).normalize_or(Vec3::Z);
+    (east, north)
+}
+
+/// Advance the weather clock, follow the camera's region and crossfade the
+/// environment toward that region's current kind.
+fn update_weather_state(
+    config: Res<WeatherConfig>,
+    mut state: ResMut<WeatherState>,
+    camera: Query<&GlobalTransform, With<GameCamera>>,
+    time: Res<Time>,
+) {
+    let Ok(camera_transform) = camera.single() else {
+        return;
+    };
+    state.clock += time.delta_secs();
+    let epoch = state.epoch(&config);
+    let region = WeatherRegion::from_up(up_at(camera_transform.translation(), config.planet_center));
+
+    // Entering a new region starts its machine from scratch (it replays to the
+    // current epoch, so the result matches `weather_at`).
+    if state.region.as_ref().is_none_or(|r| r.region != region) {
+        state.region = Some(RegionWeather::new(config.seed, region));
+    }
+    let kind = state
+        .region
+        .as_mut()
+        .map(|r| r.advance_to(config.seed, epoch))
+        .unwrap_or(WeatherKind::Clear);
+
+    if kind != state.kind {
+        info!("Weather: {:?} -> {:?} (region {})", state.kind, kind, region.0);
+        state.from = state.current;
+        state.kind = kind;
+        state.blend = 0.0;
+    }
+
+    if state.blend < 1.0 {
+        state.blend =
+            (state.blend + time.delta_secs() / config.transition_seconds.max(f32::EPSILON)).min(1.0);
+    }
+    let t = state.blend * state.blend * (3.0 - 2.0 * state.blend); // smoothstep
+    let target = state.kind.params();
+    state.current = state.from.lerp(&target, t);
+}
+
+/// Point the wind along the region's heading on the local tangent plane.
+fn update_wind(
+    config: Res<WeatherConfig>,
+    state: Res<WeatherState>,
+    mut wind: ResMut<Wind>,
+    camera: Query<&GlobalTransform, With<GameCamera>>,
+) {
+    let Ok(camera_transform) = camera.single() else {
+        return;
+    };
+    let Some(region) = state.region.as_ref() else {
+        return;
+    };
+    let up = up_at(camera_transform.translation(), config.planet_center);
+    let (east, north) = tangent_basis(up);
+    let heading = wind_heading(config.seed, region.region, region.epoch);
+    wind.direction = (east * heading.cos() + north * heading.sin()).normalize_or_zero();
+    wind.speed = state.current.wind_speed;
+}
@@ -0,0 +1,110 @@
+//! Rain particles around the camera.
+//!
+//! A fixed pool of streak meshes lives in a cylinder aligned to the local up at the
+//! camera. Drops fall along local gravity plus the [`Wind`], and are recycled to the
+//! top of the cylinder when they drop out of the bottom or drift out of its radius.
+//! [`WeatherParams::rain_intensity`](crate::weather::WeatherParams) decides how much
+//! of the pool is visible.
+
+use bevy::prelude::*;
+use kosim_camera::GameCamera;
+
+use crate::weather::{WeatherRegion, hash01};
+use crate::{WeatherConfig, WeatherState, Wind, tangent_basis, up_at};
+
+/// Number of drops in the pool.
+pub const RAIN_DROP_COUNT: usize = 800;
+/// Radius of the rain cylinder around the camera.
+const RAIN_RADIUS: f32 = 18.0;
+/// Height of the rain cylinder, centred on the camera.
+const RAIN_HEIGHT: f32 = 24.0;
+/// Terminal fall speed of a drop, in units/second.
+const RAIN_FALL_SPEED: f32 = 14.0;
+/// Salt used to scatter respawned drops (see [`hash01`]).
+const SALT_RAIN: u32 = 16;
+
+#[derive(Component)]
+pub struct RainDrop {
+    index: u32,
+    respawns: u64,
+}
+
+pub fn spawn_rain(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(0.015, 0.45, 0.015));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.75, 0.8, 0.9, 0.35),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    for index in 0..RAIN_DROP_COUNT as u32 {
+        commands.spawn((
+            Name::new("RainDrop"),
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::IDENTITY,
+            Visibility::Hidden,
+            RainDrop { index, respawns: 0 },
+        ));
+    }
+}
+
+/// A deterministic point in the rain cylinder for drop `index` on its `respawns`-th
+/// life, as `(east, north, height)` offsets in `[-1, 1]`.
+fn scatter(seed: u32, index: u32, respawns: u64) -> Vec3 {
+    let region = WeatherRegion(index);
+    Vec3::new(
+        hash01(seed, region, respawns, SALT_RAIN) * 2.0 - 1.0,
+        hash01(seed, region, respawns, SALT_RAIN + 1) * 2.0 - 1.0,
+        hash01(seed, region, respawns, SALT_RAIN + 2) * 2.0 - 1.0,
+    )
+}
+
+pub fn update_rain(
+    config: Res<WeatherConfig>,
+    state: Res<WeatherState>,
+    wind: Res<Wind>,
+    camera: Query<&GlobalTransform, With<GameCamera>>,
+    mut drops: Query<(&mut Transform, &mut Visibility, &mut RainDrop)>,
+    time: Res<Time>,
+) {
+    let Ok(camera_transform) = camera.single() else {
+        return;
+    };
+    let camera_pos = camera_transform.translation();
+    let up = up_at(camera_pos, config.planet_center);
+    let (east, north) = tangent_basis(up);
+    let velocity = -up * RAIN_FALL_SPEED + wind.velocity();
+    let orientation = Quat::from_rotation_arc(Vec3::Y, (-velocity).normalize_or(up));
+    let active = (state.current.rain_intensity.clamp(0.0, 1.0) * RAIN_DROP_COUNT as f32) as u32;
+
+    for (mut transform, mut visibility, mut drop) in &mut drops {
+        if drop.index >= active {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        }
+
+        let mut offset = transform.translation - camera_pos;
+        let height = offset.dot(up);
+        let lateral = offset - up * height;
+        let fresh = *visibility == Visibility::Hidden;
+        if fresh || height < -RAIN_HEIGHT * 0.5 || lateral.length() > RAIN_RADIUS {
+            drop.respawns += 1;
+            let s = scatter(config.seed, drop.index, drop.respawns);
+            // A fresh drop fills the whole column; recycled ones re-enter at the top.
+            let h = if fresh { s.z * RAIN_HEIGHT * 0.5 } else { RAIN_HEIGHT * 0.5 };
+            offset = east * s.x * RAIN_RADIUS + north * s.y * RAIN_RADIUS + up * h;
+            *visibility = Visibility::Visible;
+        }
+
+        offset += velocity * time.delta_secs();
+        transform.translation = camera_pos + offset;
+        transform.rotation = orientation;
+    }
+}
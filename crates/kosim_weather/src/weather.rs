@@ -0,0 +1,302 @@
+//! The deterministic weather state machine.
+//!
+//! The planet's surface is split into [`WeatherRegion`]s (a latitude/longitude
+//! grid over the direction from the planet centre). Each region steps through a
+//! Markov chain of [`WeatherKind`]s once per [`crate::WeatherConfig::cycle_seconds`]
+//! of simulated time. Every roll is a hash of `(seed, region, epoch)` rather than a
+//! stateful RNG, so the weather of any region at any epoch is reproducible from the
+//! seed alone — regardless of which regions were visited, or in what order.
+
+use std::f32::consts::PI;
+
+use bevy::math::Vec3;
+
+/// Latitude bands the sphere is split into for weather regions.
+pub const REGION_LATITUDE_BANDS: u32 = 6;
+/// Longitude sectors per latitude band.
+pub const REGION_LONGITUDE_SECTORS: u32 = 12;
+
+/// The kinds of weather a region can be in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeatherKind {
+    Clear,
+    Overcast,
+    Fog,
+    Rain,
+    Storm,
+}
+
+impl WeatherKind {
+    /// The kinds this one may change into at the next epoch, with their relative
+    /// weights. Staying put is always allowed; the rest keep the changes gradual
+    /// (e.g. a storm always passes back through rain before clearing).
+    pub fn transitions(self) -> &'static [(WeatherKind, f32)] {
+        match self {
+            WeatherKind::Clear => &[(WeatherKind::Clear, 0.6), (WeatherKind::Overcast, 0.4)],
+            WeatherKind::Overcast => &[
+                (WeatherKind::Overcast, 0.3),
+                (WeatherKind::Clear, 0.3),
+                (WeatherKind::Fog, 0.2),
+                (WeatherKind::Rain, 0.2),
+            ],
+            WeatherKind::Fog => &[(WeatherKind::Fog, 0.5), (WeatherKind::Overcast, 0.5)],
+            WeatherKind::Rain => &[
+                (WeatherKind::Rain, 0.4),
+                (WeatherKind::Overcast, 0.4),
+                (WeatherKind::Storm, 0.2),
+            ],
+            WeatherKind::Storm => &[(WeatherKind::Storm, 0.3), (WeatherKind::Rain, 0.7)],
+        }
+    }
+
+    /// Pick the next kind from [`Self::transitions`] using `roll` in `[0, 1)`.
+    pub fn next(self, roll: f32) -> WeatherKind {
+        let options = self.transitions();
+        let total: f32 = options.iter().map(|(_, weight)| weight).sum();
+        let mut remaining = roll * total;
+        for &(kind, weight) in options {
+            if remaining < weight {
+                return kind;
+            }
+            remaining -= weight;
+        }
+        options.last().map(|(kind, _)| *kind).unwrap_or(self)
+    }
+
+    /// The environment parameters this kind settles to once fully blended in.
+    pub fn params(self) -> WeatherParams {
+        match self {
+            WeatherKind::Clear => WeatherParams {
+                fog_density: 0.0,
+                fog_ambient: 0.0,
+                rain_intensity: 0.0,
+                wind_speed: 1.5,
+                rain_volume: 0.0,
+                wind_volume: 0.1,
+            },
+            WeatherKind::Overcast => WeatherParams {
+                fog_density: 0.01,
+                fog_ambient: 0.05,
+                rain_intensity: 0.0,
+                wind_speed: 4.0,
+                rain_volume: 0.0,
+                wind_volume: 0.3,
+            },
+            WeatherKind::Fog => WeatherParams {
+                fog_density: 0.12,
+                fog_ambient: 0.2,
+                rain_intensity: 0.0,
+                wind_speed: 0.5,
+                rain_volume: 0.0,
+                wind_volume: 0.05,
+            },
+            WeatherKind::Rain => WeatherParams {
+                fog_density: 0.03,
+                fog_ambient: 0.08,
+                rain_intensity: 0.5,
+                wind_speed: 5.0,
+                rain_volume: 0.6,
+                wind_volume: 0.4,
+            },
+            WeatherKind::Storm => WeatherParams {
+                fog_density: 0.05,
+                fog_ambient: 0.1,
+                rain_intensity: 1.0,
+                wind_speed: 14.0,
+                rain_volume: 1.0,
+                wind_volume: 1.0,
+            },
+        }
+    }
+}
+
+/// Continuous environment values driven by the weather. These are what actually get
+/// crossfaded between kinds; the consumers (fog, rain, wind, audio) only read these.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherParams {
+    /// `FogVolume::density_factor` around the camera.
+    pub fog_density: f32,
+    /// `VolumetricFog::ambient_intensity` on the camera.
+    pub fog_ambient: f32,
+    /// Fraction (0..=1) of the rain particle pool that is falling.
+    pub rain_intensity: f32,
+    /// Wind speed in units/second.
+    pub wind_speed: f32,
+    /// Linear gain (0..=1) of the rain ambience loop.
+    pub rain_volume: f32,
+    /// Linear gain (0..=1) of the wind ambience loop.
+    pub wind_volume: f32,
+}
+
+impl WeatherParams {
+    /// Linear blend from `self` (t = 0) to `other` (t = 1).
+    pub fn lerp(&self, other: &WeatherParams, t: f32) -> WeatherParams {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        WeatherParams {
+            fog_density: mix(self.fog_density, other.fog_density),
+            fog_ambient: mix(self.fog_ambient, other.fog_ambient),
+            rain_intensity: mix(self.rain_intensity, other.rain_intensity),
+            wind_speed: mix(self.wind_speed, other.wind_speed),
+            rain_volume: mix(self.rain_volume, other.rain_volume),
+            wind_volume: mix(self.wind_volume, other.wind_volume),
+        }
+    }
+}
+
+/// Index of a weather region on the planet surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeatherRegion(pub u32);
+
+impl WeatherRegion {
+    /// The region containing the radial direction `up` (away from the planet centre).
+    pub fn from_up(up: Vec3) -> Self {
+        let up = up.normalize_or(Vec3::Y);
+        // Latitude in [0, 1] from south to north pole, longitude in [0, 1) around Y.
+        let latitude = up.y.clamp(-1.0, 1.0).asin() / PI + 0.5;
+        let longitude = (up.z.atan2(up.x) / (2.0 * PI)).rem_euclid(1.0);
+        let band = ((latitude * REGION_LATITUDE_BANDS as f32) as u32).min(REGION_LATITUDE_BANDS - 1);
+        let sector = ((longitude * REGION_LONGITUDE_SECTORS as f32) as u32)
+            .min(REGION_LONGITUDE_SECTORS - 1);
+        WeatherRegion(band * REGION_LONGITUDE_SECTORS + sector)
+    }
+}
+
+/// Deterministic hash of `(seed, region, epoch, salt)` to `[0, 1)`.
+pub fn hash01(seed: u32, region: WeatherRegion, epoch: u64, salt: u32) -> f32 {
+    let mut h = seed.wrapping_mul(374761393)
+        ^ region.0.wrapping_mul(668265263)
+        ^ (epoch as u32).wrapping_mul(2246822519)
+        ^ ((epoch >> 32) as u32).wrapping_mul(3266489917)
+        ^ salt.wrapping_mul(2654435761);
+    h = (h ^ (h >> 13)).wrapping_mul(1274126177);
+    h ^= h >> 16;
+    (h & 0x00ff_ffff) as f32 / 16_777_216.0
+}
+
+/// Salt for the Markov roll that picks a region's next kind.
+const SALT_TRANSITION: u32 = 1;
+/// Salt for a region's starting kind at epoch 0.
+const SALT_INITIAL: u32 = 2;
+/// Salt for a region's wind heading.
+const SALT_WIND: u32 = 3;
+
+/// The kind a region starts in at epoch 0. Mostly fair weather.
+pub fn initial_weather(seed: u32, region: WeatherRegion) -> WeatherKind {
+    let roll = hash01(seed, region, 0, SALT_INITIAL);
+    if roll < 0.5 {
+        WeatherKind::Clear
+    } else if roll < 0.8 {
+        WeatherKind::Overcast
+    } else if roll < 0.9 {
+        WeatherKind::Fog
+    } else {
+        WeatherKind::Rain
+    }
+}
+
+/// The weather of `region` at `epoch`: the initial kind stepped through the Markov
+/// chain once per epoch. Pure in its arguments, so it is what tests should assert
+/// against. Use [`RegionWeather`] to avoid replaying the chain from epoch 0 every
+/// frame.
+pub fn weather_at(seed: u32, region: WeatherRegion, epoch: u64) -> WeatherKind {
+    let mut kind = initial_weather(seed, region);
+    for e in 1..=epoch {
+        kind = kind.next(hash01(seed, region, e, SALT_TRANSITION));
+    }
+    kind
+}
+
+/// Wind heading of `region` at `epoch` as an angle (radians) about the local up,
+/// measured from the region's tangent basis. Drifts a little every epoch.
+pub fn wind_heading(seed: u32, region: WeatherRegion, epoch: u64) -> f32 {
+    let base = hash01(seed, region, 0, SALT_WIND) * 2.0 * PI;
+    let drift = (hash01(seed, region, epoch, SALT_WIND) - 0.5) * 0.5 * PI;
+    base + drift
+}
+
+/// Cached Markov chain for one region, advanced incrementally. Produces the same
+/// sequence as [`weather_at`].
+#[derive(Debug, Clone)]
+pub struct RegionWeather {
+    pub region: WeatherRegion,
+    pub epoch: u64,
+    pub kind: WeatherKind,
+}
+
+impl RegionWeather {
+    pub fn new(seed: u32, region: WeatherRegion) -> Self {
+        Self {
+            region,
+            epoch: 0,
+            kind: initial_weather(seed, region),
+        }
+    }
+
+    /// Advance to `epoch` (never backwards) and return the kind there.
+    pub fn advance_to(&mut self, seed: u32, epoch: u64) -> WeatherKind {
+        while self.epoch < epoch {
+            self.epoch += 1;
+            self.kind = self
+                .kind
+                .next(hash01(seed, self.region, self.epoch, SALT_TRANSITION));
+        }
+        self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPOCHS: u64 = 64;
+
+    fn regions() -> impl Iterator<Item = WeatherRegion> {
+        (0..REGION_LATITUDE_BANDS * REGION_LONGITUDE_SECTORS).map(WeatherRegion)
+    }
+
+    /// Every region's weather for the first [`EPOCHS`] epochs, from [`weather_at`].
+    fn sequence(seed: u32) -> Vec<WeatherKind> {
+        regions()
+            .flat_map(|region| (0..EPOCHS).map(move |epoch| weather_at(seed, region, epoch)))
+            .collect()
+    }
+
+    /// The same, stepped through each region's [`RegionWeather`] chain.
+    fn chain_sequence(seed: u32) -> Vec<WeatherKind> {
+        regions()
+            .flat_map(|region| {
+                let mut chain = RegionWeather::new(seed, region);
+                (0..EPOCHS).map(move |epoch| chain.advance_to(seed, epoch))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_weather() {
+        assert_eq!(sequence(1234), sequence(1234));
+        assert_eq!(chain_sequence(1234), chain_sequence(1234));
+    }
+
+    #[test]
+    fn region_chain_matches_weather_at() {
+        assert_eq!(chain_sequence(1234), sequence(1234));
+        assert_eq!(chain_sequence(0), sequence(0));
+    }
+
+    #[test]
+    fn different_seed_gives_different_weather() {
+        assert_ne!(sequence(1234), sequence(1235));
+        assert_ne!(chain_sequence(1234), chain_sequence(1235));
+    }
+
+    #[test]
+    fn chain_skips_epochs_like_weather_at() {
+        let region = WeatherRegion(7);
+        let mut chain = RegionWeather::new(42, region);
+        assert_eq!(chain.advance_to(42, 10), weather_at(42, region, 10));
+        assert_eq!(chain.advance_to(42, 40), weather_at(42, region, 40));
+        // Never steps backwards.
+        assert_eq!(chain.advance_to(42, 5), weather_at(42, region, 40));
+    }
+}
@@ -0,0 +1,246 @@
+//! Voxel-native collision: lightweight colliders built straight from the voxel
+//! field, and ray queries against the field itself.
+//!
+//! A finest chunk's trimesh collider costs the physics world ~1 ms of main-thread
+//! registration, which is what rate-limits [`crate::attach_queued_colliders`].
+//! [`voxel_box_collider`] instead greedily merges the chunk's solid voxels into a
+//! handful of cuboids and returns them as one compound collider — a few dozen boxes
+//! register in a fraction of that. [`VoxelWorld::raycast`] answers ray queries from
+//! the field directly, with no collider at all (e.g. the player's ground probe while
+//! a chunk's collider is still queued), and [`VoxelWorld::surface_normal`] gives the
+//! smooth slope of the terrain that the boxes' axis-aligned faces lose.
+//!
+//! Boxes and rays both work in *sample-centred* space: voxel `i` is treated as the
+//! cell `[i - 0.5, i + 0.5)` around the grid point the mesher samples it at, so box
+//! faces land on the same edge midpoints as the rendered isosurface.
+
+use avian3d::prelude::Collider;
+use bevy::math::{IVec3, Quat, Vec3};
+
+use crate::VoxelWorld;
+
+/// Which collider the finest streamed chunks get.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ChunkColliderKind {
+    /// An exact trimesh of the chunk's render mesh. Expensive to register.
+    Trimesh,
+    /// Greedily merged voxel boxes (see [`voxel_box_collider`]). Cheap to register
+    /// and matches the mesh to within half a voxel.
+    #[default]
+    VoxelBoxes,
+}
+
+/// A hit from [`VoxelWorld::raycast`].
+#[derive(Clone, Copy, Debug)]
+pub struct VoxelRayHit {
+    /// The solid voxel that was hit.
+    pub voxel: IVec3,
+    /// World-space hit point on the voxel's face.
+    pub point: Vec3,
+    /// Outward normal of the face that was entered (axis-aligned).
+    pub normal: Vec3,
+    /// Distance from the ray origin to `point`.
+    pub distance: f32,
+}
+
+impl VoxelWorld {
+    /// World-space centre of voxel `v`'s sample-centred cell (see module docs).
+    #[inline]
+    pub fn voxel_center(&self, v: IVec3) -> Vec3 {
+        self.config.origin + v.as_vec3() * self.config.min_voxel_size
+    }
+
+    /// The voxel whose sample-centred cell contains world position `p`.
+    #[inline]
+    pub fn voxel_at(&self, p: Vec3) -> IVec3 {
+        ((p - self.config.origin) / self.config.min_voxel_size + Vec3::splat(0.5))
+            .floor()
+            .as_ivec3()
+    }
+
+    /// Smoothed outward surface normal of the voxel field near `p`: the negated
+    /// occupancy gradient over a ball of `radius` voxels around it. Unlike a voxel
+    /// face normal this follows the terrain's actual slope (a staircase reads as a
+    /// ramp). `None` where the ball is all solid or all empty.
+    pub fn surface_normal(&self, p: Vec3, radius: i32) -> Option<Vec3> {
+        let center = self.voxel_at(p);
+        let mut gradient = Vec3::ZERO;
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                for z in -radius..=radius {
+                    let offset = IVec3::new(x, y, z);
+                    if offset.length_squared() > radius * radius {
+                        continue;
+                    }
+                    let v = center + offset;
+                    if self.is_solid_voxel(v.x as i64, v.y as i64, v.z as i64) {
+                        gradient -= offset.as_vec3();
+                    }
+                }
+            }
+        }
+        gradient.try_normalize()
+    }
+
+    /// Cast a ray through the voxel field (Amanatides & Woo grid traversal) and
+    /// return the first solid voxel within `max_distance`. A ray starting inside a
+    /// solid voxel hits it at distance zero.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<VoxelRayHit> {
+        let dir = direction.try_normalize()?;
+        let mvs = self.config.min_voxel_size;
+        // Ray in voxel units, shifted so cell `i` spans `[i, i + 1)`.
+        let start = (origin - self.config.origin) / mvs + Vec3::splat(0.5);
+        let mut cell = start.floor().as_ivec3();
+
+        if self.is_solid_voxel(cell.x as i64, cell.y as i64, cell.z as i64) {
+            return Some(VoxelRayHit {
+                voxel: cell,
+                point: origin,
+                normal: -dir,
+                distance: 0.0,
+            });
+        }
+
+        let step = IVec3::new(
+            if dir.x > 0.0 { 1 } else { -1 },
+            if dir.y > 0.0 { 1 } else { -1 },
+            if dir.z > 0.0 { 1 } else { -1 },
+        );
+        // Ray parameter (in voxel units) to cross one whole cell on each axis, and to
+        // reach the first boundary on each axis.
+        let delta = Vec3::new(
+            (1.0 / dir.x).abs(),
+            (1.0 / dir.y).abs(),
+            (1.0 / dir.z).abs(),
+        );
+        // An axis the ray is parallel to never reaches a boundary.
+        let first_crossing = |s: f32, c: i32, d: f32, delta: f32| -> f32 {
+            if d > 0.0 {
+                (c as f32 + 1.0 - s) * delta
+            } else if d < 0.0 {
+                (s - c as f32) * delta
+            } else {
+                f32::INFINITY
+            }
+        };
+        let mut t_max = Vec3::new(
+            first_crossing(start.x, cell.x, dir.x, delta.x),
+            first_crossing(start.y, cell.y, dir.y, delta.y),
+            first_crossing(start.z, cell.z, dir.z, delta.z),
+        );
+
+        let max_t = max_distance / mvs;
+        loop {
+            // Step along whichever axis reaches its next boundary first.
+            let (t, normal) = if t_max.x <= t_max.y && t_max.x <= t_max.z {
+                cell.x += step.x;
+                let t = t_max.x;
+                t_max.x += delta.x;
+                (t, Vec3::new(-step.x as f32, 0.0, 0.0))
+            } else if t_max.y <= t_max.z {
+                cell.y += step.y;
+                let t = t_max.y;
+                t_max.y += delta.y;
+                (t, Vec3::new(0.0, -step.y as f32, 0.0))
+            } else {
+                cell.z += step.z;
+                let t = t_max.z;
+                t_max.z += delta.z;
+                (t, Vec3::new(0.0, 0.0, -step.z as f32))
+            };
+            if t > max_t {
+                return None;
+            }
+            if self.is_solid_voxel(cell.x as i64, cell.y as i64, cell.z as i64) {
+                let distance = t * mvs;
+                return Some(VoxelRayHit {
+                    voxel: cell,
+                    point: origin + dir * distance,
+                    normal,
+                    distance,
+                });
+            }
+        }
+    }
+}
+
+/// Build a compound collider for the chunk `[region_min, region_min + size)` by
+/// greedily merging its solid voxels into as few axis-aligned boxes as possible
+/// (runs in x, then rows in y, then slabs in z). Pure over `world`, so it runs on the
+/// mesh pool next to the chunk's mesh. `None` if the chunk has no solid voxels.
+pub fn voxel_box_collider(world: &VoxelWorld, region_min: IVec3, size: i64) -> Option<Collider> {
+    let n = size as usize;
+    let index = |x: usize, y: usize, z: usize| (z * n + y) * n + x;
+
+    let mut solid = vec![false; n * n * n];
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                solid[index(x, y, z)] = world.is_solid_voxel(
+                    region_min.x as i64 + x as i64,
+                    region_min.y as i64 + y as i64,
+                    region_min.z as i64 + z as i64,
+                );
+            }
+        }
+    }
+
+    let mvs = world.config.min_voxel_size;
+    let mut used = vec![false; n * n * n];
+    let mut boxes = Vec::new();
+    let free = |solid: &[bool], used: &[bool], i: usize| solid[i] && !used[i];
+
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                if !free(&solid, &used, index(x, y, z)) {
+                    continue;
+                }
+                // Extend a run along x.
+                let mut w = 1;
+                while x + w < n && free(&solid, &used, index(x + w, y, z)) {
+                    w += 1;
+                }
+                // Extend the run into a rectangle along y.
+                let mut h = 1;
+                'grow_y: while y + h < n {
+                    for dx in 0..w {
+                        if !free(&solid, &used, index(x + dx, y + h, z)) {
+                            break 'grow_y;
+                        }
+                    }
+                    h += 1;
+                }
+                // Extend the rectangle into a box along z.
+                let mut d = 1;
+                'grow_z: while z + d < n {
+                    for dy in 0..h {
+                        for dx in 0..w {
+                            if !free(&solid, &used, index(x + dx, y + dy, z + d)) {
+                                break 'grow_z;
+                            }
+                        }
+                    }
+                    d += 1;
+                }
+                for dz in 0..d {
+                    for dy in 0..h {
+                        for dx in 0..w {
+                            used[index(x + dx, y + dy, z + dz)] = true;
+                        }
+                    }
+                }
+
+                // Sample-centred: voxel i's cell is [i - 0.5, i + 0.5).
+                let min = region_min.as_vec3() + Vec3::new(x as f32, y as f32, z as f32)
+                    - Vec3::splat(0.5);
+                let extent = Vec3::new(w as f32, h as f32, d as f32);
+                let center = world.config.origin + (min + extent * 0.5) * mvs;
+                let dims = extent * mvs;
+                boxes.push((center, Quat::IDENTITY, Collider::cuboid(dims.x, dims.y, dims.z)));
+            }
+        }
+    }
+
+    (!boxes.is_empty()).then(|| Collider::compound(boxes))
+}
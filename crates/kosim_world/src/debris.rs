@@ -0,0 +1,239 @@
+//! Falling debris: voxel clusters that an edit cut loose from the terrain.
+//!
+//! After voxels are removed, [`detach_floating_clusters`] flood-fills from the solid
+//! neighbours of every removed voxel. A fill that runs out of voxels before
+//! [`MAX_DEBRIS_VOXELS`] is a small island no longer attached to the planet: it is
+//! removed from the static field and re-spawned as a dynamic rigid body with a
+//! convex-hull collider and its own exposed-face mesh. A fill that exceeds the budget
+//! is treated as anchored — anything that large is, in practice, the planet itself —
+//! and so is everything it visited, which cuts short any later fill that reaches it.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use avian3d::prelude::{Collider, LinearVelocity, RigidBody};
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+
+use crate::ChunkManager;
+use crate::VoxelWorld;
+use crate::edit::VoxelsEdited;
+use crate::voxel::VoxelMaterial;
+
+/// Largest cluster (in voxels) that can break off. Bounds the flood fill, which
+/// runs on the main thread in the frame of the edit.
+pub const MAX_DEBRIS_VOXELS: usize = 4096;
+/// Gravity applied to debris toward the planet centre (units/s²). Physics runs
+/// with zero global gravity because "down" is radial; matches the player's.
+const DEBRIS_GRAVITY: f32 = 9.81;
+
+/// The six face-neighbour offsets.
+const NEIGHBOURS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+/// A loose voxel cluster simulated as a rigid body.
+#[derive(Component)]
+pub struct Debris {
+    /// Number of voxels in the cluster.
+    pub voxels: usize,
+}
+
+/// Result of flood-filling from one solid voxel.
+enum Fill {
+    /// A loose cluster, with the material of every voxel in it.
+    Loose(HashMap<IVec3, VoxelMaterial>),
+    /// The fill ran over budget or reached a voxel already known to be anchored;
+    /// holds every voxel it visited, all of which are anchored too.
+    Anchored(Vec<IVec3>),
+}
+
+/// Flood-fill the 6-connected solid cluster containing `start`. Stops as soon as it
+/// holds more than `budget` voxels or reaches one in `anchored`.
+fn flood_cluster(
+    world: &VoxelWorld,
+    start: IVec3,
+    budget: usize,
+    anchored: &HashSet<IVec3>,
+) -> Option<Fill> {
+    let material_at = |v: IVec3| world.voxel_material(v.x as i64, v.y as i64, v.z as i64);
+    let mut cluster = HashMap::new();
+    let mut queue = VecDeque::new();
+    cluster.insert(start, material_at(start)?);
+    queue.push_back(start);
+    while let Some(v) = queue.pop_front() {
+        for offset in NEIGHBOURS {
+            let n = v + offset;
+            if cluster.contains_key(&n) {
+                continue;
+            }
+            if let Some(material) = material_at(n) {
+                if anchored.contains(&n) || cluster.len() >= budget {
+                    return Some(Fill::Anchored(cluster.into_keys().collect()));
+                }
+                cluster.insert(n, material);
+                queue.push_back(n);
+            }
+        }
+    }
+    Some(Fill::Loose(cluster))
+}
+
+/// Check the neighbourhood of every removed voxel for clusters that lost their
+/// connection to the terrain, and turn each one into [`Debris`].
+pub fn detach_floating_clusters(
+    manager: Option<ResMut<ChunkManager>>,
+    mut edited: MessageReader<VoxelsEdited>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(mut manager) = manager else {
+        edited.clear();
+        return;
+    };
+    // Only removals can disconnect anything.
+    let removed: Vec<IVec3> = edited
+        .read()
+        .flat_map(|batch| batch.changes.iter())
+        .filter(|(_, value)| value.is_none())
+        .map(|(v, _)| *v)
+        .collect();
+    if removed.is_empty() {
+        return;
+    }
+
+    let world = manager.world.clone();
+    // Everything an over-budget fill touched is attached to the terrain, so a later
+    // fill that reaches any of it can stop right there instead of re-walking the
+    // whole budget.
+    let mut anchored: HashSet<IVec3> = HashSet::new();
+    let mut loose: HashSet<IVec3> = HashSet::new();
+    let mut clusters = Vec::new();
+    for v in removed {
+        for offset in NEIGHBOURS {
+            let seed = v + offset;
+            if anchored.contains(&seed)
+                || loose.contains(&seed)
+                || !world.is_solid_voxel(seed.x as i64, seed.y as i64, seed.z as i64)
+            {
+                continue;
+            }
+            match flood_cluster(&world, seed, MAX_DEBRIS_VOXELS, &anchored) {
+                Some(Fill::Loose(cluster)) => {
+                    loose.extend(cluster.keys().copied());
+                    clusters.push(cluster);
+                }
+                Some(Fill::Anchored(visited)) => anchored.extend(visited),
+                None => {}
+            }
+        }
+    }
+    if clusters.is_empty() {
+        return;
+    }
+
+    // Clear the clusters from the static field in this same frame. `apply_edits`
+    // also rebuilds voxel-box colliders for the live physics chunks, swapped in
+    // before the next physics step, so the new bodies do not start inside the
+    // colliders they came out of; chunks that only re-mesh (non-physics) have no
+    // collider to overlap. Not announced through `VoxelsEdited`: nothing else could
+    // detach, since each cluster was isolated.
+    manager.apply_edits(
+        clusters
+            .iter()
+            .flat_map(|cluster| cluster.keys().map(|&v| (v, None)))
+            .collect(),
+    );
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        perceptual_roughness: 0.95,
+        metallic: 0.0,
+        ..default()
+    });
+    for cluster in clusters {
+        spawn_debris(&mut commands, &mut meshes, material.clone(), &world, &cluster);
+    }
+}
+
+fn spawn_debris(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    material: Handle<StandardMaterial>,
+    world: &VoxelWorld,
+    cluster: &HashMap<IVec3, VoxelMaterial>,
+) {
+    let centroid = cluster.keys().map(|&v| world.voxel_center(v)).sum::<Vec3>() / cluster.len() as f32;
+    let half = world.config.min_voxel_size * 0.5;
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut colors: Vec<[f32; 4]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+    for (&v, material) in cluster {
+        let center = world.voxel_center(v) - centroid;
+        let color = material.linear_rgba();
+        for offset in NEIGHBOURS {
+            if cluster.contains_key(&(v + offset)) {
+                continue; // interior face
+            }
+            let normal = offset.as_vec3();
+            // Two tangents such that `u × w = normal`, giving counter-clockwise winding.
+            let (u, w) = normal.any_orthonormal_pair();
+            let (u, w) = if u.cross(w).dot(normal) > 0.0 { (u, w) } else { (w, u) };
+            let base = positions.len() as u32;
+            for (su, sw) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                positions.push((center + (normal + u * su + w * sw) * half).to_array());
+                normals.push(normal.to_array());
+                colors.push(color);
+            }
+            indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+    }
+
+    // Every vertex of the exposed faces; the hull ignores the interior duplicates.
+    let Some(collider) = Collider::convex_hull(positions.iter().map(|&p| Vec3::from(p)).collect())
+    else {
+        return;
+    };
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+
+    commands.spawn((
+        Name::new("Debris"),
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(material),
+        Transform::from_translation(centroid),
+        RigidBody::Dynamic,
+        collider,
+        Debris {
+            voxels: cluster.len(),
+        },
+    ));
+}
+
+/// Pull debris toward the planet centre.
+pub fn apply_debris_gravity(
+    manager: Option<Res<ChunkManager>>,
+    mut debris: Query<(&Transform, &mut LinearVelocity), With<Debris>>,
+    time: Res<Time>,
+) {
+    let Some(manager) = manager else {
+        return;
+    };
+    let center = manager.world.center();
+    for (transform, mut velocity) in &mut debris {
+        let down = (center - transform.translation).normalize_or_zero();
+        velocity.0 += down * DEBRIS_GRAVITY * time.delta_secs();
+    }
+}
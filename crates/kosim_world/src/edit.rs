@@ -0,0 +1,224 @@
+//! Runtime voxel edits layered over the procedural planet.
+//!
+//! The generator is immutable; removed and placed voxels live in a sparse
+//! [`VoxelEdits`] overlay that [`VoxelWorld`] consults before falling back to the
+//! generator. Edits are applied copy-on-write: [`VoxelWorld::with_edits`] returns a
+//! new snapshot sharing the generator, and [`ChunkManager::apply_edits`] swaps it in.
+//! Meshing tasks already in flight keep the snapshot they started with, so they never
+//! observe a half-applied edit; the chunks an edit touches are simply re-meshed
+//! against the new snapshot.
+//!
+//! Re-meshing takes a frame or two, but collision must not lag the edit: a dug-out
+//! floor would hold the player up and placed voxels would be walked through. So
+//! edited physics chunks get a voxel-box collider rebuilt in the same frame (see
+//! [`swap_edited_colliders`]), even where chunks otherwise use trimeshes; the
+//! re-meshed chunk then swaps in with its usual collider.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use avian3d::prelude::{Collider, RigidBody};
+use bevy::math::I64Vec3;
+use bevy::prelude::*;
+
+use crate::collision;
+use crate::voxel::VoxelMaterial;
+use crate::{ChunkManager, VoxelWorld, lod};
+
+/// One voxel change: `None` removes the voxel (air), `Some` places that material.
+pub type VoxelChange = (IVec3, Option<VoxelMaterial>);
+
+/// The sparse edit overlay. Shared between world snapshots until it next changes.
+#[derive(Default, Clone)]
+pub struct VoxelEdits {
+    /// Edited voxels and what they now hold.
+    pub voxels: HashMap<IVec3, Option<VoxelMaterial>>,
+    /// Finest-chunk coordinates (`voxel / CELLS_PER_CHUNK`) containing any edit. Lets
+    /// [`VoxelWorld::region_has_surface`] keep edited regions in the LOD walk without
+    /// scanning every edited voxel.
+    pub chunks: HashSet<IVec3>,
+}
+
+impl VoxelEdits {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// The edit at `v`, if any: `Some(None)` for a removed voxel.
+    #[inline]
+    pub fn get(&self, v: IVec3) -> Option<Option<VoxelMaterial>> {
+        self.voxels.get(&v).copied()
+    }
+
+    /// Does any edit fall inside the voxel region `[region_min, region_min + size)`?
+    pub fn touches_region(&self, region_min: IVec3, size: i64) -> bool {
+        let max = region_min.as_i64vec3() + I64Vec3::splat(size);
+        self.chunks.iter().any(|c| {
+            let lo = c.as_i64vec3() * lod::CELLS_PER_CHUNK;
+            let hi = lo + I64Vec3::splat(lod::CELLS_PER_CHUNK);
+            lo.cmplt(max).all() && hi.cmpgt(region_min.as_i64vec3()).all()
+        })
+    }
+}
+
+/// Finest-chunk coordinate containing voxel `v`.
+#[inline]
+fn chunk_of(v: IVec3) -> IVec3 {
+    IVec3::new(
+        v.x.div_euclid(lod::CELLS_PER_CHUNK as i32),
+        v.y.div_euclid(lod::CELLS_PER_CHUNK as i32),
+        v.z.div_euclid(lod::CELLS_PER_CHUNK as i32),
+    )
+}
+
+impl VoxelWorld {
+    /// A new snapshot of this world with `changes` applied on top of its edits.
+    /// Changes that restore the generator's own value drop out of the overlay.
+    pub fn with_edits(&self, changes: impl IntoIterator<Item = VoxelChange>) -> VoxelWorld {
+        let mut edits = (*self.edits).clone();
+        for (v, value) in changes {
+            let generated = self
+                .generator
+                .material_at_voxel(v.x as i64, v.y as i64, v.z as i64);
+            if value == generated {
+                edits.voxels.remove(&v);
+            } else {
+                edits.voxels.insert(v, value);
+            }
+        }
+        edits.chunks = edits.voxels.keys().map(|&v| chunk_of(v)).collect();
+        VoxelWorld {
+            generator: self.generator.clone(),
+            dim: self.dim,
+            config: self.config.clone(),
+            edits: Arc::new(edits),
+        }
+    }
+}
+
+/// Request a batch of voxel changes. Applied once per frame by [`apply_voxel_edits`],
+/// before chunk streaming runs.
+#[derive(Message, Event, Clone)]
+pub struct EditVoxels {
+    pub changes: Vec<VoxelChange>,
+}
+
+/// Sent after a batch of edits has been applied to the world, with the voxels whose
+/// contents actually changed.
+#[derive(Message, Event, Clone)]
+pub struct VoxelsEdited {
+    pub changes: Vec<VoxelChange>,
+}
+
+impl ChunkManager {
+    /// Apply `changes` to the world and re-mesh every live chunk they touch. Returns
+    /// the changes that actually altered a voxel.
+    pub fn apply_edits(&mut self, changes: Vec<VoxelChange>) -> Vec<VoxelChange> {
+        let changed: Vec<VoxelChange> = changes
+            .into_iter()
+            .filter(|&(v, value)| {
+                self.world.voxel_material(v.x as i64, v.y as i64, v.z as i64) != value
+            })
+            .collect();
+        if changed.is_empty() {
+            return changed;
+        }
+        self.world = Arc::new(self.world.with_edits(changed.iter().copied()));
+
+        // Meshing samples one voxel past the chunk (normals, topsoil march), so a
+        // chunk is stale if a change lies within a small apron around it.
+        const APRON: i32 = 2;
+        let (lo, hi) = changed.iter().fold(
+            (IVec3::MAX, IVec3::MIN),
+            |(lo, hi), &(v, _)| (lo.min(v), hi.max(v)),
+        );
+        let (lo, hi) = (lo - IVec3::splat(APRON), hi + IVec3::splat(APRON));
+        let touched = |key: &lod::ChunkKey| {
+            let (region_min, size, _) = *key;
+            let region_max = region_min + IVec3::splat(size as i32);
+            region_min.cmple(hi).all() && region_max.cmpgt(lo).all()
+        };
+
+        let stale: Vec<lod::ChunkKey> = self
+            .active
+            .keys()
+            .chain(self.pending.keys())
+            .filter(|k| touched(k))
+            .copied()
+            .collect();
+        // Voxel boxes for one finest chunk are cheap enough to rebuild right here, so
+        // physics sees the edit this frame whatever the chunk collider kind; the
+        // re-meshed chunk brings its own collider (a trimesh, if so configured) when
+        // it swaps in.
+        for key in &stale {
+            let (region_min, size, _) = *key;
+            if size != lod::CELLS_PER_CHUNK || !self.physics.contains(key) {
+                continue;
+            }
+            let Some(&entity) = self.active.get(key) else {
+                continue;
+            };
+            // A still-queued collider would overwrite the fresh one.
+            self.collider_queue.retain(|(queued, _, _)| *queued != entity);
+            let collider = collision::voxel_box_collider(&self.world, region_min, size);
+            self.collider_swaps.push((entity, collider));
+        }
+        for key in stale {
+            self.remeshing.insert(key);
+            let task = crate::spawn_chunk_task(self.world.clone(), key, self.physics.contains(&key));
+            self.pending.insert(key, task);
+        }
+        // Chunks cached as empty may have gained a surface.
+        self.empty.retain(|k| !touched(k));
+        // Edits can create surface where the LOD walk used to prune (e.g. digging into
+        // solid interior), so force the desired set to be recomputed.
+        self.last_observers.clear();
+        changed
+    }
+}
+
+/// Swap the colliders rebuilt by [`ChunkManager::apply_edits`] onto their live
+/// chunks, in the frame of the edit.
+pub fn swap_edited_colliders(manager: Option<ResMut<ChunkManager>>, mut commands: Commands) {
+    let Some(mut manager) = manager else {
+        return;
+    };
+    for (entity, collider) in manager.collider_swaps.drain(..) {
+        let Ok(mut chunk) = commands.get_entity(entity) else {
+            continue;
+        };
+        match collider {
+            Some(collider) => {
+                chunk.insert((RigidBody::Static, collider));
+            }
+            // Dug out completely.
+            None => {
+                chunk.remove::<(RigidBody, Collider)>();
+            }
+        }
+    }
+}
+
+/// Drain [`EditVoxels`] requests into the world and announce what changed.
+pub fn apply_voxel_edits(
+    manager: Option<ResMut<ChunkManager>>,
+    mut requests: MessageReader<EditVoxels>,
+    mut edited: MessageWriter<VoxelsEdited>,
+) {
+    let Some(mut manager) = manager else {
+        requests.clear();
+        return;
+    };
+    let changes: Vec<VoxelChange> = requests
+        .read()
+        .flat_map(|request| request.changes.iter().copied())
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+    let changed = manager.apply_edits(changes);
+    if !changed.is_empty() {
+        edited.write(VoxelsEdited { changes: changed });
+    }
+}
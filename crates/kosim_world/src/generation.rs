@@ -46,7 +46,7 @@ impl PlanetGenerator {
     }
 
     /// Surface radius (voxels) in the direction of the unit vector `dir`.
-    fn surface_radius(&self, dir: [f64; 3]) -> f64 {
+    pub fn surface_radius(&self, dir: [f64; 3]) -> f64 {
         // A few large features across the sphere.
         const FREQ: f64 = 2.5;
         let n = self
@@ -0,0 +1,164 @@
+//! In-game inspector for terrain LOD and streaming, toggled by
+//! [`Bindings::action_toggle_world_inspector`].
+//!
+//! Draws every streamed chunk's bounds as a gizmo, coloured by LOD level, with a tick
+//! on each face that carries Transvoxel transition cells. Chunks with a collider get
+//! an inner box, pending (meshing) chunks are drawn in yellow and retiring chunks in
+//! red. A panel lists the [`ChunkManager`] bookkeeping counts.
+
+use avian3d::prelude::Collider;
+use bevy::prelude::*;
+use kosim_input::binding::Bindings;
+
+use crate::fade::Fade;
+use crate::{ChunkManager, TerrainChunk, lod};
+
+/// Chunks farther than this from every camera are not drawn (the coarse ones are huge
+/// and would fill the screen with lines).
+const INSPECTOR_MAX_DISTANCE: f32 = 256.0;
+const PENDING_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+const RETIRING_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+const COLLIDER_COLOR: Color = Color::srgb(0.2, 1.0, 0.4);
+const TRANSITION_COLOR: Color = Color::WHITE;
+
+#[derive(Resource, Default)]
+pub struct WorldInspector {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+pub struct WorldInspectorText;
+
+/// Colour for a chunk of `size` voxels: one hue per LOD level, finest first.
+fn lod_color(size: i64) -> Color {
+    let level = (size / lod::CELLS_PER_CHUNK).max(1).ilog2();
+    Color::hsl((level as f32 * 47.0) % 360.0, 0.85, 0.55)
+}
+
+pub fn spawn_world_inspector(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(5.0),
+            bottom: Val::Px(5.0),
+            padding: UiRect::all(Val::Px(5.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.05, 0.05, 0.05, 0.75)),
+        BorderColor::all(Color::srgb(0.6, 0.6, 0.6)),
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/mononoki-Bold.ttf"),
+            font_size: 11.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Visibility::Hidden,
+        WorldInspectorText,
+    ));
+}
+
+pub fn toggle_world_inspector(
+    keys: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<Bindings>,
+    mut inspector: ResMut<WorldInspector>,
+    mut panel: Query<&mut Visibility, With<WorldInspectorText>>,
+) {
+    if !keys.just_pressed(key_bindings.action_toggle_world_inspector) {
+        return;
+    }
+    inspector.enabled = !inspector.enabled;
+    info!("World inspector: {}", inspector.enabled);
+    for mut visibility in &mut panel {
+        *visibility = if inspector.enabled { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+pub fn draw_world_inspector(
+    inspector: Res<WorldInspector>,
+    manager: Option<Res<ChunkManager>>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    chunks: Query<(&TerrainChunk, &Fade, Has<Collider>)>,
+    mut panel: Query<&mut Text, With<WorldInspectorText>>,
+    mut gizmos: Gizmos,
+) {
+    if !inspector.enabled {
+        return;
+    }
+    let Some(manager) = manager else {
+        return;
+    };
+    let config = &manager.world.config;
+    let mvs = config.min_voxel_size;
+    let camera_positions: Vec<Vec3> = cameras.iter().map(|t| t.translation()).collect();
+    let bounds = |key: lod::ChunkKey| {
+        let (region_min, size, _) = key;
+        let extent = size as f32 * mvs;
+        let center = config.origin + (region_min.as_vec3() + Vec3::splat(size as f32 * 0.5)) * mvs;
+        (center, extent)
+    };
+    let visible = |center: Vec3, extent: f32| {
+        camera_positions.is_empty()
+            || camera_positions
+                .iter()
+                .any(|p| center.distance(*p) - extent <= INSPECTOR_MAX_DISTANCE)
+    };
+
+    let mut colliders = 0;
+    for (chunk, fade, has_collider) in &chunks {
+        colliders += has_collider as usize;
+        let (center, extent) = bounds(chunk.0);
+        if !visible(center, extent) {
+            continue;
+        }
+        let (_, size, sides) = chunk.0;
+        let color = if fade.retiring { RETIRING_COLOR } else { lod_color(size) };
+        gizmos.cube(Transform::from_translation(center).with_scale(Vec3::splat(extent)), color);
+        if has_collider {
+            gizmos.cube(
+                Transform::from_translation(center).with_scale(Vec3::splat(extent * 0.9)),
+                COLLIDER_COLOR,
+            );
+        }
+        // A tick from each transition face's centre inward, in `ChunkKey` bit order.
+        const FACES: [Vec3; 6] = [Vec3::NEG_X, Vec3::X, Vec3::NEG_Y, Vec3::Y, Vec3::NEG_Z, Vec3::Z];
+        for (i, normal) in FACES.into_iter().enumerate() {
+            if sides & (1 << i) != 0 {
+                let face = center + normal * extent * 0.5;
+                gizmos.line(face, face - normal * extent * 0.25, TRANSITION_COLOR);
+            }
+        }
+    }
+    for &key in manager.pending.keys() {
+        let (center, extent) = bounds(key);
+        if visible(center, extent) {
+            gizmos.cube(
+                Transform::from_translation(center).with_scale(Vec3::splat(extent * 0.95)),
+                PENDING_COLOR,
+            );
+        }
+    }
+
+    if let Ok(mut text) = panel.single_mut() {
+        text.0 = format!(
+            "terrain chunks\n\
+             active:         {}\n\
+             pending:        {}\n\
+             retiring:       {}\n\
+             empty:          {}\n\
+             collider_queue: {}\n\
+             colliders:      {}\n\
+             remeshing:      {}\n\
+             edits:          {}",
+            manager.active.len(),
+            manager.pending.len(),
+            manager.retiring.len(),
+            manager.empty.len(),
+            manager.collider_queue.len(),
+            colliders,
+            manager.remeshing.len(),
+            manager.world.edits.voxels.len(),
+        );
+    }
+}
@@ -14,14 +14,20 @@ use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, OnceLock};
 
 use avian3d::prelude::{Collider, RigidBody};
+use bevy::math::DVec3;
 use bevy::prelude::*;
 use bevy::tasks::{AsyncComputeTaskPool, Task, TaskPool, TaskPoolBuilder, block_on, futures_lite::future};
 
+pub mod collision;
+pub mod debris;
+pub mod edit;
 pub mod fade;
 pub mod generation;
+pub mod inspector;
 pub mod lod;
 pub mod voxel;
 
+use collision::ChunkColliderKind;
 use fade::{ChunkFade, ChunkMaterial, DISSOLVE_SECONDS, FADE_SECONDS, Fade, RETIRE_SECONDS};
 use voxel::VoxelMaterial;
 
@@ -52,6 +58,8 @@ pub struct WorldConfig {
     pub rebuild_distance: f32,
     /// Seed for procedural generation.
     pub seed: u32,
+    /// Collider built for each finest chunk (see [`collision`]).
+    pub chunk_collider: ChunkColliderKind,
 }
 
 impl Default for WorldConfig {
@@ -71,6 +79,7 @@ impl Default for WorldConfig {
             // how often the (whole-world) remesh runs.
             rebuild_distance: 4.0,
             seed: 0,
+            chunk_collider: ChunkColliderKind::default(),
         }
     }
 }
@@ -80,10 +89,13 @@ impl Default for WorldConfig {
 /// stored, so the world uses no memory proportional to its size.
 #[derive(Resource)]
 pub struct VoxelWorld {
-    pub generator: generation::PlanetGenerator,
+    /// Shared between edit snapshots (see [`edit`]).
+    pub generator: Arc<generation::PlanetGenerator>,
     /// Voxels per axis (`2^max_depth`).
     pub dim: i64,
     pub config: WorldConfig,
+    /// Runtime removals/placements layered over the generator.
+    pub edits: Arc<edit::VoxelEdits>,
 }
 
 impl VoxelWorld {
@@ -92,30 +104,58 @@ impl VoxelWorld {
         let dim = 1i64 << config.max_depth;
         let generator = generation::PlanetGenerator::new(dim, config.seed);
         Self {
-            generator,
+            generator: Arc::new(generator),
             dim,
             config,
+            edits: Arc::new(edit::VoxelEdits::default()),
         }
     }
 
+    /// World-space centre of the planet.
+    #[inline]
+    pub fn center(&self) -> Vec3 {
+        self.config.origin + Vec3::splat(self.dim as f32 * 0.5) * self.config.min_voxel_size
+    }
+
+    /// Height of world position `p` above the generated surface beneath it (negative
+    /// underground). Edits are ignored, so this is cheap enough to ask every frame.
+    pub fn altitude(&self, p: Vec3) -> f32 {
+        let offset = ((p - self.center()) / self.config.min_voxel_size).as_dvec3();
+        let dir = offset.normalize_or(DVec3::Y);
+        let surface = self.generator.surface_radius(dir.to_array());
+        ((offset.length() - surface) * self.config.min_voxel_size as f64) as f32
+    }
+
     /// Is the voxel at integer voxel coordinates `(x, y, z)` solid?
     #[inline]
     pub fn is_solid_voxel(&self, x: i64, y: i64, z: i64) -> bool {
+        if !self.edits.is_empty()
+            && let Some(edit) = self.edits.get(IVec3::new(x as i32, y as i32, z as i32))
+        {
+            return edit.is_some();
+        }
         self.generator.is_solid(x, y, z)
     }
 
-    /// Material of the voxel at `(x, y, z)`, or `None` if it is outside the planet.
+    /// Material of the voxel at `(x, y, z)`, or `None` if it is empty.
     #[inline]
     pub fn voxel_material(&self, x: i64, y: i64, z: i64) -> Option<VoxelMaterial> {
+        if !self.edits.is_empty()
+            && let Some(edit) = self.edits.get(IVec3::new(x as i32, y as i32, z as i32))
+        {
+            return edit;
+        }
         self.generator.material_at_voxel(x, y, z)
     }
 
     /// Might the voxel region `[region_min, region_min + size)` contain any surface?
-    /// Used to prune empty air / solid-interior regions from the LOD walk.
+    /// Used to prune empty air / solid-interior regions from the LOD walk. Regions
+    /// holding edits are always kept: an edit can open a surface anywhere.
     #[inline]
     pub fn region_has_surface(&self, region_min: IVec3, size: i64) -> bool {
         self.generator
             .region_has_surface(region_min.x as i64, region_min.y as i64, region_min.z as i64, size)
+            || (!self.edits.is_empty() && self.edits.touches_region(region_min, size))
     }
 }
 
@@ -139,6 +179,44 @@ fn mesh_pool() -> &'static TaskPool {
     })
 }
 
+/// Streams terrain around its entity. The desired chunk set is the union over every
+/// observer — each region gets the finest LOD any observer asks for — so a player,
+/// a detached free camera and a distant NPC can all have detail at once.
+///
+/// A `Camera3d` without an observer of its own streams as [`ChunkObserver::default`]
+/// (render-only). Finest chunks only get colliders around observers with `physics`.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ChunkObserver {
+    /// This observer's detail falloff (see [`WorldConfig::lod_threshold`]); `None`
+    /// uses the world's.
+    pub lod_threshold: Option<f32>,
+    /// Distance beyond which this observer adds no detail. Unbounded by default; an
+    /// NPC that only needs ground under its feet can use a few chunks' worth.
+    pub radius: f32,
+    /// Give the finest chunks this observer refines to a collider.
+    pub physics: bool,
+}
+
+impl Default for ChunkObserver {
+    fn default() -> Self {
+        Self {
+            lod_threshold: None,
+            radius: f32::INFINITY,
+            physics: false,
+        }
+    }
+}
+
+impl ChunkObserver {
+    /// An observer that also needs collision around it (e.g. the player).
+    pub fn physics() -> Self {
+        Self {
+            physics: true,
+            ..default()
+        }
+    }
+}
+
 /// Marks a rendered leaf-chunk entity with the chunk it represents.
 #[derive(Component)]
 pub struct TerrainChunk(pub lod::ChunkKey);
@@ -146,7 +224,7 @@ pub struct TerrainChunk(pub lod::ChunkKey);
 /// Owns the streamed voxel world and the currently-rendered set of LOD chunks.
 ///
 /// Each visible leaf chunk is its own entity/mesh keyed by [`lod::ChunkKey`]. As the
-/// camera moves, only chunks whose LOD changed are added or removed, and their
+/// observers move, only chunks whose LOD changed are added or removed, and their
 /// meshing runs on the async compute pool — so the main thread never blocks on a
 /// remesh. The collider is separate and full-resolution (see [`setup_world`]).
 #[derive(Resource)]
@@ -171,9 +249,18 @@ pub struct ChunkManager {
     collider_queue: Vec<(Entity, Vec3, Collider)>,
     /// The desired-set computation runs off the main thread (it walks/balances the
     /// whole LOD tree). At most one is in flight; its result is diffed when ready.
-    desired_task: Option<Task<Vec<lod::ChunkKey>>>,
-    /// Camera position the desired set was last computed for.
-    last_camera_pos: Vec3,
+    desired_task: Option<Task<lod::DesiredChunks>>,
+    /// Observers the desired set was last computed for. Cleared to force a rebuild.
+    last_observers: Vec<(Entity, Vec3, ChunkObserver)>,
+    /// Finest chunks that should carry a collider (see [`lod::DesiredChunks`]).
+    physics: HashSet<lod::ChunkKey>,
+    /// Chunks being re-meshed after a voxel edit. Their replacement swaps in opaque
+    /// with its collider attached immediately, rather than dithering in and waiting in
+    /// `collider_queue`, so an edit never flickers or drops the player.
+    remeshing: HashSet<lod::ChunkKey>,
+    /// Colliders rebuilt for live chunks by an edit, waiting to be swapped in (see
+    /// [`edit::swap_edited_colliders`]). `None` removes a chunk's collider.
+    collider_swaps: Vec<(Entity, Option<Collider>)>,
 }
 
 impl ChunkManager {
@@ -181,6 +268,11 @@ impl ChunkManager {
     pub fn world(&self) -> &VoxelWorld {
         &self.world
     }
+
+    /// Chunks live in the world, and chunks still meshing (e.g. for benchmarks).
+    pub fn chunk_counts(&self) -> (usize, usize) {
+        (self.active.len(), self.pending.len())
+    }
 }
 
 /// Registers the voxel world: generates the sample scene and full-resolution static
@@ -192,10 +284,16 @@ impl Plugin for KosimWorldPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<WorldConfig>()
             .add_plugins(MaterialPlugin::<ChunkMaterial>::default())
-            .add_systems(Startup, setup_world)
+            .add_message::<edit::EditVoxels>()
+            .add_message::<edit::VoxelsEdited>()
+            .init_resource::<inspector::WorldInspector>()
+            .add_systems(Startup, (setup_world, inspector::spawn_world_inspector))
             .add_systems(
                 Update,
                 (
+                    edit::apply_voxel_edits,
+                    debris::detach_floating_clusters,
+                    edit::swap_edited_colliders,
                     schedule_chunk_meshing,
                     apply_finished_chunks,
                     attach_queued_colliders,
@@ -203,7 +301,12 @@ impl Plugin for KosimWorldPlugin {
                     animate_fades,
                 )
                     .chain(),
-            );
+            )
+            .add_systems(
+                Update,
+                (inspector::toggle_world_inspector, inspector::draw_world_inspector).chain(),
+            )
+            .add_systems(FixedUpdate, debris::apply_debris_gravity);
     }
 }
 
@@ -224,8 +327,9 @@ fn setup_world(
 
     // No whole-world collider: it was O(dim^3) to build and a giant static trimesh,
     // which caps the world size. Instead each *finest* streamed chunk near the player
-    // gets its own trimesh collider (see `apply_finished_chunks`), so collision cost
-    // is bounded regardless of how large the planet is.
+    // gets its own collider — voxel boxes or a trimesh, per `WorldConfig::chunk_collider`
+    // (see `apply_finished_chunks`) — so collision cost is bounded regardless of how
+    // large the planet is.
 
     commands.insert_resource(ChunkManager {
         world: Arc::new(world),
@@ -236,17 +340,22 @@ fn setup_world(
         empty: HashSet::new(),
         collider_queue: Vec::new(),
         desired_task: None,
-        // A sentinel far from any real camera forces a first pass on the first Update.
-        last_camera_pos: Vec3::splat(f32::INFINITY),
+        // No observers recorded forces a first pass on the first Update.
+        last_observers: Vec::new(),
+        physics: HashSet::new(),
+        remeshing: HashSet::new(),
+        collider_swaps: Vec::new(),
     });
 }
 
-/// When the camera has moved far enough, diff the desired chunk set against what is
+/// When an observer has moved far enough, diff the desired chunk set against what is
 /// live: despawn chunks that are no longer wanted and spawn async meshing tasks for
 /// newly wanted ones. Unchanged chunks are left untouched (the incremental win).
 fn schedule_chunk_meshing(
     mut manager: ResMut<ChunkManager>,
-    camera: Query<&GlobalTransform, With<Camera3d>>,
+    mut commands: Commands,
+    observers: Query<(Entity, &GlobalTransform, &ChunkObserver)>,
+    cameras: Query<(Entity, &GlobalTransform), (With<Camera3d>, Without<ChunkObserver>)>,
     mut fades: Query<(&mut Fade, &MeshMaterial3d<ChunkMaterial>)>,
     mut materials: ResMut<Assets<ChunkMaterial>>,
 ) {
@@ -257,9 +366,10 @@ fn schedule_chunk_meshing(
         .desired_task
         .as_mut()
         .and_then(|task| block_on(future::poll_once(task)));
-    if let Some(desired_vec) = ready {
+    if let Some(result) = ready {
         manager.desired_task = None;
-        let desired: HashSet<lod::ChunkKey> = desired_vec.into_iter().collect();
+        let desired: HashSet<lod::ChunkKey> = result.chunks.into_iter().collect();
+        let physics: HashSet<lod::ChunkKey> = result.physics.into_iter().collect();
 
         // Forget cached-empty chunks that are no longer wanted, to bound memory.
         manager.empty.retain(|k| desired.contains(k));
@@ -304,10 +414,34 @@ fn schedule_chunk_meshing(
         }
 
         manager.pending.retain(|key, _| desired.contains(key));
+        let ChunkManager { pending, remeshing, .. } = &mut *manager;
+        remeshing.retain(|key| pending.contains_key(key));
+
+        // Chunks that moved out of physics range drop their collider; live chunks
+        // that moved into it are re-meshed in place (opaque, collider attached on
+        // arrival — the same path an edit takes). Pending ones are re-requested.
+        let lost: Vec<lod::ChunkKey> = manager.physics.difference(&physics).copied().collect();
+        for key in lost {
+            if let Some(&entity) = manager.active.get(&key) {
+                commands.entity(entity).remove::<(RigidBody, Collider)>();
+                manager.collider_queue.retain(|(queued, _, _)| *queued != entity);
+            }
+        }
+        let gained: Vec<lod::ChunkKey> = physics.difference(&manager.physics).copied().collect();
+        for key in gained {
+            let live = manager.active.contains_key(&key);
+            if live || manager.pending.contains_key(&key) {
+                if live {
+                    manager.remeshing.insert(key);
+                }
+                let task = spawn_chunk_task(manager.world.clone(), key, true);
+                manager.pending.insert(key, task);
+            }
+        }
+        manager.physics = physics;
 
         // Spawn async meshing for newly wanted chunks (on the dedicated mesh pool —
         // see `mesh_pool` for why not `AsyncComputeTaskPool`).
-        let pool = mesh_pool();
         for key in desired {
             if manager.active.contains_key(&key)
                 || manager.pending.contains_key(&key)
@@ -315,40 +449,66 @@ fn schedule_chunk_meshing(
             {
                 continue;
             }
-            let world = manager.world.clone();
-            let (region_min, size, sides) = key;
-            let task = pool.spawn(async move {
-                let mesh = lod::mesh_one_chunk(&world, region_min, size, sides);
-                // Build the collider here (off the main thread); only finest chunks,
-                // which are next to the player, need one.
-                let collider = if size == lod::CELLS_PER_CHUNK {
-                    Collider::trimesh_from_mesh(&mesh)
-                } else {
-                    None
-                };
-                (mesh, collider)
-            });
+            let task = spawn_chunk_task(manager.world.clone(), key, manager.physics.contains(&key));
             manager.pending.insert(key, task);
         }
     }
 
-    // 2. Start a new desired-set computation off-thread when the camera has moved far
-    // enough and none is already running.
+    // 2. Start a new desired-set computation off-thread when an observer has moved far
+    // enough (or one came or went) and none is already running.
     if manager.desired_task.is_none() {
-        let Ok(camera_transform) = camera.single() else {
+        let mut current: Vec<(Entity, Vec3, ChunkObserver)> = observers
+            .iter()
+            .map(|(entity, transform, observer)| (entity, transform.translation(), *observer))
+            .chain(cameras.iter().map(|(entity, transform)| {
+                (entity, transform.translation(), ChunkObserver::default())
+            }))
+            .collect();
+        if current.is_empty() {
             return;
-        };
-        let camera_pos = camera_transform.translation();
-        if camera_pos.distance(manager.last_camera_pos) >= manager.world.config.rebuild_distance {
-            manager.last_camera_pos = camera_pos;
+        }
+        current.sort_unstable_by_key(|(entity, _, _)| *entity);
+
+        let rebuild_distance = manager.world.config.rebuild_distance;
+        let unchanged = current.len() == manager.last_observers.len()
+            && current.iter().zip(&manager.last_observers).all(|(now, then)| {
+                now.0 == then.0 && now.2 == then.2 && now.1.distance(then.1) < rebuild_distance
+            });
+        if !unchanged {
+            let views: Vec<lod::ObserverView> = current.iter().map(|&(_, p, o)| (p, o)).collect();
+            manager.last_observers = current;
             let world = manager.world.clone();
             manager.desired_task = Some(
-                AsyncComputeTaskPool::get().spawn(async move { lod::desired_chunks(&world, camera_pos) }),
+                AsyncComputeTaskPool::get().spawn(async move { lod::desired_chunks(&world, &views) }),
             );
         }
     }
 }
 
+/// Mesh chunk `key` (and build its collider, if `physics`) against `world` on the dedicated mesh
+/// pool — see [`mesh_pool`] for why not `AsyncComputeTaskPool`.
+pub(crate) fn spawn_chunk_task(
+    world: Arc<VoxelWorld>,
+    key: lod::ChunkKey,
+    physics: bool,
+) -> Task<(Mesh, Option<Collider>)> {
+    let (region_min, size, sides) = key;
+    mesh_pool().spawn(async move {
+        let mesh = lod::mesh_one_chunk(&world, region_min, size, sides);
+        // Build the collider here (off the main thread); only finest chunks next to
+        // a physics observer need one.
+        let collider = if physics && size == lod::CELLS_PER_CHUNK {
+            match world.config.chunk_collider {
+                ChunkColliderKind::Trimesh => Collider::trimesh_from_mesh(&mesh),
+                ChunkColliderKind::VoxelBoxes => collision::voxel_box_collider(&world, region_min, size),
+            }
+        } else {
+            None
+        };
+        (mesh, collider)
+    })
+}
+
 /// Poll in-flight chunk meshes; spawn an entity for each one that finished this
 /// frame, starting it dissolving in from transparent.
 fn apply_finished_chunks(
@@ -356,20 +516,29 @@ fn apply_finished_chunks(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ChunkMaterial>>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
 ) {
+    let camera_positions: Vec<Vec3> = cameras.iter().map(|t| t.translation()).collect();
     // Apply at most this many finished chunks per frame. A fast flight can finish a
     // few hundred at once; handing them all to the renderer in one frame spikes the
     // GPU mesh upload/prepare. Spreading them over frames is invisible thanks to the
-    // dither fade-in.
+    // dither fade-in. Re-meshes after an edit are exempt, so an edit shows within a
+    // frame or two even in the middle of a streaming wave.
     const MAX_APPLY_PER_FRAME: usize = 24;
 
     let mut finished: Vec<(lod::ChunkKey, Mesh, Option<Collider>)> = Vec::new();
     let mut done_keys: Vec<lod::ChunkKey> = Vec::new();
-    for (key, task) in manager.pending.iter_mut() {
-        if finished.len() >= MAX_APPLY_PER_FRAME {
-            break;
+    let mut streamed = 0;
+    let ChunkManager { pending, remeshing, .. } = &mut *manager;
+    for (key, task) in pending.iter_mut() {
+        let remesh = remeshing.contains(key);
+        if !remesh && streamed >= MAX_APPLY_PER_FRAME {
+            continue;
         }
         if let Some((mesh, collider)) = block_on(future::poll_once(&mut *task)) {
+            if !remesh {
+                streamed += 1;
+            }
             finished.push((*key, mesh, collider));
             done_keys.push(*key);
         }
@@ -379,10 +548,17 @@ fn apply_finished_chunks(
     }
 
     for (key, mesh, collider) in finished {
+        // A re-mesh after an edit replaces the live chunk in place (see `edit`).
+        let remeshed = manager.remeshing.remove(&key);
+
         // Chunks with no surface (air / solid interior) render nothing: cache the key
         // so it is never re-meshed and never spawned as an invisible entity.
         if mesh.indices().map(|i| i.is_empty()).unwrap_or(true) {
             manager.empty.insert(key);
+            // An edit can also dig a chunk's whole surface away.
+            if let Some(old) = manager.active.remove(&key) {
+                commands.entity(old).despawn();
+            }
             continue;
         }
 
@@ -391,9 +567,9 @@ fn apply_finished_chunks(
         let handle = meshes.add(mesh);
         // Each chunk owns its material so it can fade independently. Vertex colours
         // carry the terrain material; base_color is white so they pass through.
-        // `last_camera_pos` is close enough for the spawn-frame morph factor;
-        // `update_morph_factors` keeps it current from then on.
-        let morph = lod::morph_factor(&manager.world, key, manager.last_camera_pos);
+        // `update_morph_factors` keeps the morph factor current from the next
+        // frame on; without a camera the chunk starts at full detail.
+        let morph = nearest_morph_factor(&manager.world, key, &camera_positions);
         let material = materials.add(ChunkMaterial {
             base: StandardMaterial {
                 base_color: Color::WHITE,
@@ -406,7 +582,7 @@ fn apply_finished_chunks(
                 ..default()
             },
             extension: ChunkFade {
-                params: Vec4::new(0.0, morph, 0.0, 0.0),
+                params: Vec4::new(if remeshed { 1.0 } else { 0.0 }, morph, 0.0, 0.0),
                 array: Some(manager.terrain_array.clone()),
             },
         });
@@ -420,18 +596,23 @@ fn apply_finished_chunks(
             Transform::IDENTITY,
             TerrainChunk(key),
             Fade {
-                value: 0.0,
+                value: if remeshed { 1.0 } else { 0.0 },
                 retiring: false,
                 timer: 0.0,
             },
         ));
         let entity = chunk.id();
-        if let Some(collider) = collider {
-            let (region_min, size, _) = key;
-            let center = manager.world.config.origin
-                + (region_min.as_vec3() + Vec3::splat(size as f32 * 0.5))
-                    * manager.world.config.min_voxel_size;
-            manager.collider_queue.push((entity, center, collider));
+        // The chunk may have left physics range while it was meshing.
+        if let Some(collider) = collider.filter(|_| manager.physics.contains(&key)) {
+            if remeshed {
+                commands.entity(entity).insert((RigidBody::Static, collider));
+            } else {
+                let (region_min, size, _) = key;
+                let center = manager.world.config.origin
+                    + (region_min.as_vec3() + Vec3::splat(size as f32 * 0.5))
+                        * manager.world.config.min_voxel_size;
+                manager.collider_queue.push((entity, center, collider));
+            }
         }
         // Replace any prior entity for this key (e.g. a re-requested chunk).
         if let Some(old) = manager.active.insert(key, entity) {
@@ -440,6 +621,17 @@ fn apply_finished_chunks(
     }
 }
 
+/// A chunk's geomorph factor as seen from the nearest of `cameras`: with several
+/// viewports the most detailed view wins, so no camera sees a chunk morphed toward
+/// its parent while it is close. Full detail (0) when there is no camera.
+fn nearest_morph_factor(world: &VoxelWorld, key: lod::ChunkKey, cameras: &[Vec3]) -> f32 {
+    cameras
+        .iter()
+        .map(|&p| lod::morph_factor(world, key, p))
+        .reduce(f32::min)
+        .unwrap_or(0.0)
+}
+
 /// Keep every chunk's geomorph factor tracking its camera distance. The factor is a
 /// per-chunk material uniform (see [`lod::morph_factor`] for why it is not computed
 /// in the shader from the view). It is quantised, and a material is only marked
@@ -448,16 +640,16 @@ fn apply_finished_chunks(
 /// morph band.
 fn update_morph_factors(
     manager: Res<ChunkManager>,
-    camera: Query<&GlobalTransform, With<Camera3d>>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
     mut materials: ResMut<Assets<ChunkMaterial>>,
     chunks: Query<(&TerrainChunk, &MeshMaterial3d<ChunkMaterial>)>,
 ) {
-    let Ok(camera_transform) = camera.single() else {
+    let camera_positions: Vec<Vec3> = cameras.iter().map(|t| t.translation()).collect();
+    if camera_positions.is_empty() {
         return;
-    };
-    let camera_pos = camera_transform.translation();
+    }
     for (chunk, material) in &chunks {
-        let morph = lod::morph_factor(&manager.world, chunk.0, camera_pos);
+        let morph = nearest_morph_factor(&manager.world, chunk.0, &camera_positions);
         // Compare through `get` first: `get_mut` flags the asset as modified (a GPU
         // re-prepare) even when nothing changed.
         if materials
@@ -470,30 +662,44 @@ fn update_morph_factors(
     }
 }
 
-/// Drain a few queued colliders per frame, nearest to the camera first. Registering
+/// Drain a few queued colliders per frame, nearest to a physics observer first (the
+/// ones that asked for the colliders — not the camera, which in free cam may be far
+/// from the player). Registering
 /// a static trimesh collider costs the physics world real main-thread time (~1 ms
 /// each); attaching a whole wave of freshly meshed chunks in one frame was the main
 /// source of movement lag spikes. Latency here is invisible: colliders only matter
-/// right next to the player, and those chunks sort to the front.
+/// right next to the player, and those chunks sort to the front. Voxel-box colliders
+/// are far cheaper to register, so many more are drained per frame.
 fn attach_queued_colliders(
     mut manager: ResMut<ChunkManager>,
     mut commands: Commands,
-    camera: Query<&GlobalTransform, With<Camera3d>>,
+    observers: Query<(&GlobalTransform, &ChunkObserver)>,
 ) {
-    const MAX_PER_FRAME: usize = 4;
+    let max_per_frame: usize = match manager.world.config.chunk_collider {
+        ChunkColliderKind::Trimesh => 4,
+        ChunkColliderKind::VoxelBoxes => 32,
+    };
     if manager.collider_queue.is_empty() {
         return;
     }
     // Sort farthest-first so the nearest chunks pop off the tail.
-    if let Ok(camera_transform) = camera.single() {
-        let camera_pos = camera_transform.translation();
-        manager.collider_queue.sort_unstable_by(|a, b| {
-            let da = a.1.distance_squared(camera_pos);
-            let db = b.1.distance_squared(camera_pos);
-            db.total_cmp(&da)
-        });
+    let physics_positions: Vec<Vec3> = observers
+        .iter()
+        .filter(|(_, observer)| observer.physics)
+        .map(|(transform, _)| transform.translation())
+        .collect();
+    if !physics_positions.is_empty() {
+        let nearest = |center: Vec3| {
+            physics_positions
+                .iter()
+                .map(|p| center.distance_squared(*p))
+                .fold(f32::INFINITY, f32::min)
+        };
+        manager
+            .collider_queue
+            .sort_unstable_by(|a, b| nearest(b.1).total_cmp(&nearest(a.1)));
     }
-    let take = manager.collider_queue.len().min(MAX_PER_FRAME);
+    let take = manager.collider_queue.len().min(max_per_frame);
     let at = manager.collider_queue.len() - take;
     for (entity, _, collider) in manager.collider_queue.split_off(at) {
         // The chunk may have been despawned (retired/replaced) while queued.
@@ -1,8 +1,9 @@
 //! Camera-driven level-of-detail chunk selection and isosurface meshing.
 //!
 //! The world is split into a distance-driven set of leaf *chunks*; [`desired_chunks`]
-//! chooses which chunks (and at what LOD) the camera should see, enforcing a 2:1
-//! balance so Transvoxel transition cells only ever bridge a single LOD jump.
+//! chooses which chunks (and at what LOD) the observers should see — the union of
+//! what each [`ChunkObserver`] wants — enforcing a 2:1 balance so Transvoxel
+//! transition cells only ever bridge a single LOD jump.
 //! [`mesh_one_chunk`] meshes one chunk with the `transvoxel` crate, fed a binary
 //! `+1/-1` field so every edge crossing lands at the exact midpoint (`t = 0.5`) —
 //! grid-aligned, no density interpolation. It runs off the main thread.
@@ -21,8 +22,8 @@ use transvoxel::prelude::{Block, extract_from_fn};
 use transvoxel::transition_sides::{TransitionSide, TransitionSides, no_side};
 use transvoxel::voxel_source::BlockDims;
 
-use crate::VoxelWorld;
 use crate::voxel::VoxelMaterial;
+use crate::{ChunkObserver, VoxelWorld};
 
 /// Marching cells per axis in a leaf chunk. A region is meshed with cell step
 /// `region_voxels / CELLS_PER_CHUNK`, so a chunk keeps a bounded triangle budget
@@ -76,40 +77,84 @@ pub fn morph_factor(world: &VoxelWorld, key: ChunkKey, camera_pos: Vec3) -> f32
     (s * 64.0).round() / 64.0
 }
 
-/// The set of leaf chunks the camera should currently see. Walks the chunk octree
-/// choosing an LOD per region by distance, enforces a 2:1 balance (face-adjacent
-/// chunks differ by at most one level, which Transvoxel transition cells require),
-/// then tags each chunk with the faces where its neighbour is one level finer. Cheap
-/// — no meshing.
-pub fn desired_chunks(world: &VoxelWorld, camera_pos: Vec3) -> Vec<ChunkKey> {
+/// An observer as snapshotted for the off-thread walk: its position and settings.
+pub type ObserverView = (Vec3, ChunkObserver);
+
+/// The result of [`desired_chunks`].
+pub struct DesiredChunks {
+    /// Every leaf chunk wanted by any observer.
+    pub chunks: Vec<ChunkKey>,
+    /// The finest chunks a physics observer itself refined to — the ones that get a
+    /// collider. Finest chunks produced only by other observers, or only by the 2:1
+    /// balance, stay render-only.
+    pub physics: Vec<ChunkKey>,
+}
+
+/// The set of leaf chunks the observers should currently see. Walks the chunk octree
+/// choosing an LOD per region by distance (the finest any observer asks for),
+/// enforces a 2:1 balance (face-adjacent chunks differ by at most one level, which
+/// Transvoxel transition cells require), then tags each chunk with the faces where
+/// its neighbour is one level finer. Cheap — no meshing.
+pub fn desired_chunks(world: &VoxelWorld, observers: &[ObserverView]) -> DesiredChunks {
     let mut leaves = HashSet::new();
-    collect_leaves(world, IVec3::ZERO, world.dim, camera_pos, &mut leaves);
+    collect_leaves(world, IVec3::ZERO, world.dim, observers, &mut leaves);
     balance_leaves(&mut leaves, world.dim);
 
-    leaves
+    let chunks: Vec<ChunkKey> = leaves
         .iter()
         .map(|&(region_min, size)| {
             let sides = transition_sides(&leaves, region_min, size, world.dim);
             (region_min, size, sides)
         })
-        .collect()
+        .collect();
+
+    let physics_observers: Vec<ObserverView> =
+        observers.iter().filter(|(_, o)| o.physics).copied().collect();
+    let mvs = world.config.min_voxel_size;
+    let physics = chunks
+        .iter()
+        .filter(|&&(region_min, size, _)| {
+            if size != CELLS_PER_CHUNK || physics_observers.is_empty() {
+                return false;
+            }
+            // Finest because a physics observer subdivided its parent?
+            let parent_size = size * 2;
+            let parent_min = IVec3::new(
+                (region_min.x as i64).div_euclid(parent_size) as i32 * parent_size as i32,
+                (region_min.y as i64).div_euclid(parent_size) as i32 * parent_size as i32,
+                (region_min.z as i64).div_euclid(parent_size) as i32 * parent_size as i32,
+            );
+            let center = world.config.origin
+                + (parent_min.as_vec3() + Vec3::splat(parent_size as f32 * 0.5)) * mvs;
+            should_subdivide(world, center, parent_size as f32 * mvs, &physics_observers)
+        })
+        .copied()
+        .collect();
+
+    DesiredChunks { chunks, physics }
 }
 
-/// Is a region of edge length `world_size` centred at `center` close enough to the
-/// camera to warrant subdividing it for more detail?
-fn should_subdivide(world: &VoxelWorld, center: Vec3, world_size: f32, camera_pos: Vec3) -> bool {
-    let dist = center.distance(camera_pos).max(1.0e-3);
-    world_size / dist > world.config.lod_threshold
+/// Is a region of edge length `world_size` centred at `center` close enough to any
+/// observer to warrant subdividing it for more detail?
+fn should_subdivide(world: &VoxelWorld, center: Vec3, world_size: f32, observers: &[ObserverView]) -> bool {
+    // Half the region's diagonal: an observer's radius reaches the region's bounding
+    // sphere, so a large region the observer stands in is never cut off.
+    let reach = world_size * 0.87;
+    observers.iter().any(|(position, observer)| {
+        let dist = center.distance(*position).max(1.0e-3);
+        let threshold = observer.lod_threshold.unwrap_or(world.config.lod_threshold);
+        dist - reach <= observer.radius && world_size / dist > threshold
+    })
 }
 
 /// Walk the virtual chunk octree, subdividing a region while it is large relative to
-/// its camera distance (down to `CELLS_PER_CHUNK` voxels), recording a leaf region
-/// `(region_min, size)` wherever subdivision stops.
+/// its distance from some observer (down to `CELLS_PER_CHUNK` voxels), recording a
+/// leaf region `(region_min, size)` wherever subdivision stops.
 fn collect_leaves(
     world: &VoxelWorld,
     region_min: IVec3,
     size: i64,
-    camera_pos: Vec3,
+    observers: &[ObserverView],
     out: &mut HashSet<(IVec3, i64)>,
 ) {
     // Skip regions (and their whole subtree) that contain no surface — most of a
@@ -124,7 +169,7 @@ fn collect_leaves(
     let center =
         world.config.origin + (region_min.as_vec3() + Vec3::splat(size as f32 * 0.5)) * mvs;
 
-    if size > CELLS_PER_CHUNK && should_subdivide(world, center, world_size, camera_pos) {
+    if size > CELLS_PER_CHUNK && should_subdivide(world, center, world_size, observers) {
         let half = (size / 2) as i32;
         for i in 0..8 {
             let offset = IVec3::new(
@@ -132,7 +177,7 @@ fn collect_leaves(
                 ((i >> 1) & 1) as i32 * half,
                 ((i >> 2) & 1) as i32 * half,
             );
-            collect_leaves(world, region_min + offset, size / 2, camera_pos, out);
+            collect_leaves(world, region_min + offset, size / 2, observers, out);
         }
     } else {
         out.insert((region_min, size));
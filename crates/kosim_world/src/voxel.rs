@@ -16,10 +16,12 @@ pub enum VoxelMaterial {
     Grass,
     Sand,
     Snow,
+    /// Overgrown rock the player can climb. Never generated; placed by edits.
+    Vines,
 }
 
 /// Number of distinct voxel materials — the layer count of the terrain texture array.
-pub const MATERIAL_COUNT: u32 = 5;
+pub const MATERIAL_COUNT: u32 = 6;
 
 impl VoxelMaterial {
     /// Layer index of this material in the terrain texture array (`0..MATERIAL_COUNT`).
@@ -36,9 +38,32 @@ impl VoxelMaterial {
             VoxelMaterial::Grass,
             VoxelMaterial::Sand,
             VoxelMaterial::Snow,
+            VoxelMaterial::Vines,
         ]
     }
 
+    /// The material's name in data files, e.g. `"stone"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            VoxelMaterial::Stone => "stone",
+            VoxelMaterial::Dirt => "dirt",
+            VoxelMaterial::Grass => "grass",
+            VoxelMaterial::Sand => "sand",
+            VoxelMaterial::Snow => "snow",
+            VoxelMaterial::Vines => "vines",
+        }
+    }
+
+    /// The material called `name` (see [`VoxelMaterial::name`]).
+    pub fn from_name(name: &str) -> Option<VoxelMaterial> {
+        VoxelMaterial::all().into_iter().find(|m| m.name() == name)
+    }
+
+    /// Whether the player can climb a face of this material.
+    pub fn is_climbable(self) -> bool {
+        matches!(self, VoxelMaterial::Vines)
+    }
+
     /// The base sRGB colour for this material.
     pub fn srgb(self) -> Color {
         match self {
@@ -47,6 +72,7 @@ impl VoxelMaterial {
             VoxelMaterial::Grass => Color::srgb(0.28, 0.52, 0.20),
             VoxelMaterial::Sand => Color::srgb(0.76, 0.70, 0.50),
             VoxelMaterial::Snow => Color::srgb(0.92, 0.94, 0.98),
+            VoxelMaterial::Vines => Color::srgb(0.16, 0.36, 0.12),
         }
     }
 
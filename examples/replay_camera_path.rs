@@ -0,0 +1,156 @@
+//! Replay a recorded camera path headlessly, to time terrain streaming along it.
+//!
+//! Loads a [`CameraPath`] (the file the game records with F6, or the one named by
+//! the first argument) and moves a [`ChunkObserver`] along it with
+//! [`CameraPath::sample_playback`], a fixed [`REPLAY_STEP`] of path time per frame.
+//! Chunk streaming and meshing run exactly as in the game, but with no window and
+//! no GPU, so this measures the CPU side (LOD walks, meshing tasks, chunk swaps)
+//! and gives the same frames on every run:
+//!
+//! ```text
+//! cargo run --release --example replay_camera_path -- kosim-camera-path.toml
+//! ```
+//!
+//! Frame times and chunk counts are logged every [`LOG_INTERVAL`] of path time, with
+//! a summary at the end.
+
+use std::time::Duration;
+
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    prelude::*,
+    render::{
+        RenderPlugin,
+        settings::{RenderCreation, WgpuSettings},
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use kosim_camera::path::{CameraPath, DEFAULT_CAMERA_PATH_FILE};
+use kosim_input::binding::Bindings;
+use kosim_utility::toml_file::TomlFile;
+use kosim_world::{ChunkManager, ChunkObserver, KosimWorldPlugin};
+
+/// Seconds of path time each frame advances.
+const REPLAY_STEP: f32 = 1.0 / 60.0;
+/// Seconds of path time between progress logs.
+const LOG_INTERVAL: f32 = 1.0;
+
+#[derive(Resource)]
+struct Replay {
+    path: CameraPath,
+    elapsed: f32,
+    next_log: f32,
+    frames: u32,
+    total: Duration,
+    slowest: Duration,
+}
+
+#[derive(Component)]
+struct ReplayObserver;
+
+fn main() -> AppExit {
+    let file = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_CAMERA_PATH_FILE.into());
+    let path = match CameraPath::load(&file) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{file}: {e}");
+            return AppExit::error();
+        }
+    };
+    println!(
+        "Replaying {file}: {} keyframes, {:.1} s",
+        path.keyframes.len(),
+        path.playback_duration()
+    );
+
+    App::new()
+        .add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                // No GPU backend: nothing is drawn, but meshes and materials are
+                // still built as assets, as in the game.
+                .set(RenderPlugin {
+                    render_creation: RenderCreation::Automatic(WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
+            ScheduleRunnerPlugin::run_loop(Duration::ZERO),
+            KosimWorldPlugin,
+        ))
+        .init_resource::<Bindings>()
+        .insert_resource(Replay {
+            path,
+            elapsed: 0.0,
+            next_log: 0.0,
+            frames: 0,
+            total: Duration::ZERO,
+            slowest: Duration::ZERO,
+        })
+        .add_systems(Startup, spawn_observer)
+        .add_systems(Update, replay_path)
+        .run()
+}
+
+fn spawn_observer(mut commands: Commands, replay: Res<Replay>) {
+    commands.spawn((
+        Name::new("ReplayObserver"),
+        replay.path.sample_playback(0.0),
+        ChunkObserver::default(),
+        ReplayObserver,
+    ));
+}
+
+/// Step the observer along the path, timing every frame, and exit at the end.
+fn replay_path(
+    mut replay: ResMut<Replay>,
+    mut observer: Query<&mut Transform, With<ReplayObserver>>,
+    manager: Option<Res<ChunkManager>>,
+    time: Res<Time<Real>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let Ok(mut transform) = observer.single_mut() else {
+        return;
+    };
+    // The first frame includes startup (world generation); leave it out.
+    if replay.frames > 0 {
+        let frame = time.delta();
+        replay.total += frame;
+        replay.slowest = replay.slowest.max(frame);
+    }
+    replay.frames += 1;
+
+    let (live, pending) = manager.map_or((0, 0), |manager| manager.chunk_counts());
+    if replay.elapsed >= replay.next_log {
+        replay.next_log += LOG_INTERVAL;
+        info!(
+            "t = {:5.1} s: frame {:6.2} ms, {live} chunks live, {pending} meshing",
+            replay.elapsed,
+            time.delta_secs() * 1000.0,
+        );
+    }
+
+    if replay.elapsed >= replay.path.playback_duration() {
+        let timed = replay.frames.saturating_sub(1).max(1);
+        info!(
+            "Replayed {} frames in {:.2} s: mean {:.2} ms, slowest {:.2} ms; {live} chunks live, {pending} still meshing",
+            replay.frames,
+            replay.total.as_secs_f32(),
+            replay.total.as_secs_f32() * 1000.0 / timed as f32,
+            replay.slowest.as_secs_f32() * 1000.0,
+        );
+        exit.write(AppExit::Success);
+        return;
+    }
+    *transform = replay.path.sample_playback(replay.elapsed);
+    replay.elapsed += REPLAY_STEP;
+}
@@ -7,7 +7,7 @@ use avian3d::{
 use bevy::{
     color::palettes::tailwind::{AMBER_400, SKY_400, ZINC_200},
     dev_tools::fps_overlay::{FpsOverlayConfig, FpsOverlayPlugin, FrameTimeGraphConfig},
-    light::{CascadeShadowConfigBuilder, DirectionalLightShadowMap, SunDisk},
+    light::{CascadeShadowConfigBuilder, DirectionalLightShadowMap, SunDisk, VolumetricLight},
     pbr::wireframe::{WireframeConfig, WireframePlugin},
     prelude::*,
     render::{
@@ -22,8 +22,14 @@ use bevy_turborand::prelude::RngPlugin;
 use kosim_camera::KosimCameraPlugin;
 use kosim_input::{InputConfig, KosimInputPlugin, binding::Bindings, input::Input};
 use kosim_interface::KosimInterfacePlugin;
-use kosim_player::{PlayerPlugin, focus::ObjectInformationComponent};
+use kosim_inventory::{KosimInventoryPlugin, world_item::WorldItem};
+use kosim_player::{
+    PlayerPlugin,
+    focus::ObjectInformationComponent,
+    interact::{InteractVerb, Interactable},
+};
 use kosim_utility::mesh::generate_plane_mesh;
+use kosim_weather::KosimWeatherPlugin;
 use kosim_world::KosimWorldPlugin;
 
 fn main() {
@@ -56,7 +62,9 @@ fn main() {
             PhysicsDebugPlugin::default(),
             PhysicsPlugins::default(),
             PlayerPlugin,
+            KosimInventoryPlugin,
             KosimWorldPlugin,
+            KosimWeatherPlugin,
             FpsOverlayPlugin {
                 config: FpsOverlayConfig {
                     enabled: true,
@@ -141,6 +149,8 @@ fn setup(
         },
         Transform::default().with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_4)),
         SunDisk::default(),
+        // Lets the weather's volumetric fog scatter sunlight.
+        VolumetricLight,
     ));
 
     // Plane
@@ -175,8 +185,14 @@ fn setup(
             name: "Yellow Box".to_string(),
             description: "A yellow box for testing".to_string(),
         },
+        Interactable::new(InteractVerb::PickUp),
     ));
 
+    // A few items to take; their bodies and meshes come from `config/items.toml`.
+    commands.spawn((WorldItem::new("stone", 12), Transform::from_xyz(3.0, 25.0, 1.0)));
+    commands.spawn((WorldItem::new("battery", 2), Transform::from_xyz(3.5, 25.0, 2.5)));
+    commands.spawn((WorldItem::new("wrench", 1), Transform::from_xyz(1.0, 25.0, 3.0)));
+
     for i in 0..6 {
         let size: f32 = 2.0 + (i as f32 * 2.0);
         let z_offset: f32 = (0..i)